@@ -9,10 +9,11 @@ extern crate log_settings;
 extern crate syntax;
 #[macro_use] extern crate log;
 
-use miri::{eval_main, run_mir_passes};
+use miri::{eval_main_with_result, run_mir_passes, EvalConfig, MainResult, Value};
 use rustc::session::Session;
 use rustc::mir::mir_map::MirMap;
 use rustc_driver::{driver, CompilerCalls, Compilation};
+use std::io::Write;
 use syntax::ast::{MetaItemKind, NestedMetaItemKind};
 
 struct MiriCompilerCalls;
@@ -38,15 +39,25 @@ impl<'a> CompilerCalls<'a> for MiriCompilerCalls {
             let entry_def_id = tcx.map.local_def_id(entry_node_id);
 
             let krate = state.hir_crate.as_ref().unwrap();
-            let mut memory_size = 100*1024*1024; // 100MB
-            let mut step_limit = 1000_000;
-            let mut stack_limit = 100;
+            let mut config = EvalConfig::default();
             let extract_int = |lit: &syntax::ast::Lit| -> u64 {
                 match lit.node {
                     syntax::ast::LitKind::Int(i, _) => i,
                     _ => state.session.span_fatal(lit.span, "expected an integer literal"),
                 }
             };
+            let extract_bool = |lit: &syntax::ast::Lit| -> bool {
+                match lit.node {
+                    syntax::ast::LitKind::Bool(b) => b,
+                    _ => state.session.span_fatal(lit.span, "expected a boolean literal"),
+                }
+            };
+            let extract_str = |lit: &syntax::ast::Lit| -> String {
+                match lit.node {
+                    syntax::ast::LitKind::Str(ref s, _) => s.to_string(),
+                    _ => state.session.span_fatal(lit.span, "expected a string literal"),
+                }
+            };
             for attr in krate.attrs.iter() {
                 match attr.node.value.node {
                     MetaItemKind::List(ref name, _) if name != "miri" => {}
@@ -55,9 +66,22 @@ impl<'a> CompilerCalls<'a> for MiriCompilerCalls {
                             NestedMetaItemKind::MetaItem(ref inner) => match inner.node {
                                 MetaItemKind::NameValue(ref name, ref value) => {
                                     match &**name {
-                                        "memory_size" => memory_size = extract_int(value) as usize,
-                                        "step_limit" => step_limit = extract_int(value),
-                                        "stack_limit" => stack_limit = extract_int(value) as usize,
+                                        "memory_size" => config.memory_size = extract_int(value) as usize,
+                                        "max_alloc_size" => config.max_alloc_size = Some(extract_int(value) as usize),
+                                        "step_limit" => config.step_limit = extract_int(value),
+                                        "stack_limit" => config.stack_limit = extract_int(value) as usize,
+                                        "deterministic_addresses" => config.deterministic_addresses = extract_bool(value),
+                                        "strict_provenance" => config.strict_provenance = extract_bool(value),
+                                        "check_uninit" => config.check_uninit = extract_bool(value),
+                                        "check_leaks" => config.check_leaks = extract_bool(value),
+                                        "arg" => config.args.push(extract_str(value)),
+                                        "env" => {
+                                            let var = extract_str(value);
+                                            match var.find('=') {
+                                                Some(i) => config.env.push((var[..i].to_owned(), var[i + 1..].to_owned())),
+                                                None => state.session.span_err(value.span, "expected `NAME=value`"),
+                                            }
+                                        }
                                         _ => state.session.span_err(item.span, "unknown miri attribute"),
                                     }
                                 }
@@ -75,9 +99,17 @@ impl<'a> CompilerCalls<'a> for MiriCompilerCalls {
                 mir_map_copy.map.insert(def_id, mir_map.map.get(&def_id).unwrap().clone());
             }
             run_mir_passes(tcx, &mut mir_map_copy);
-            eval_main(tcx, &mir_map_copy, entry_def_id, memory_size, step_limit, stack_limit);
+            let result = eval_main_with_result(tcx, &mir_map_copy, entry_def_id, &config);
 
             state.session.abort_if_errors();
+
+            // Like the compiled program would, exit with the status a `#[start]` function returned.
+            if let Some(MainResult { value: Value::ByVal(status), .. }) = result {
+                if status.bits != 0 {
+                    std::io::stdout().flush().unwrap();
+                    std::process::exit(status.bits as i32);
+                }
+            }
         });
 
         control
@@ -2,7 +2,7 @@ use std::error::Error;
 use std::fmt;
 use rustc::mir::repr as mir;
 use rustc::ty::BareFnTy;
-use memory::Pointer;
+use memory::{AllocId, Pointer};
 use rustc_const_math::ConstMathErr;
 use syntax::codemap::Span;
 
@@ -10,7 +10,11 @@ use syntax::codemap::Span;
 pub enum EvalError<'tcx> {
     FunctionPointerTyMismatch(&'tcx BareFnTy<'tcx>, &'tcx BareFnTy<'tcx>),
     NoMirFor(String),
-    DanglingPointerDeref,
+    NoSuchConstant(String),
+    DanglingPointerDeref(AllocId),
+    DeallocateNonBasePtr,
+    ReallocateNonBasePtr,
+    DoubleFree(AllocId),
     InvalidMemoryAccess,
     InvalidFunctionPointer,
     InvalidBool,
@@ -20,24 +24,53 @@ pub enum EvalError<'tcx> {
         size: usize,
         allocation_size: usize,
     },
-    ReadPointerAsBytes,
+    ReadPointerAsBytes {
+        /// The accessed bytes, or `None` if a pointer value was used as an integer directly.
+        range: Option<(Pointer, usize)>,
+    },
+    PartialPointerRead {
+        ptr: Pointer,
+        /// The offset in `ptr`'s allocation where the overlapping pointer starts.
+        pointer_start: usize,
+    },
     InvalidPointerMath,
+    PointerArithmeticOverflow,
     ReadUndefBytes,
     InvalidBoolOp(mir::BinOp),
     Unimplemented(String),
     DerefFunctionPointer,
     ExecuteMemory,
+    CopyNonOverlappingOverlaps,
     ArrayIndexOutOfBounds(Span, u64, u64),
+    /// A slice pattern projection needed a longer slice: the len followed by the minimum length.
+    SliceTooShort(Span, u64, u64),
     Math(Span, ConstMathErr),
+    DivisionByZero,
+    UncheckedDivisionByZero,
+    InexactDivision,
+    OverflowingMath,
     InvalidChar(u64),
     OutOfMemory {
         allocation_size: usize,
         memory_size: usize,
         memory_usage: usize,
     },
+    AllocationTooLarge {
+        allocation_size: usize,
+        max_allocation_size: usize,
+    },
+    InvalidGrowSize {
+        allocation_size: usize,
+        new_size: usize,
+    },
     ExecutionTimeLimitReached,
+    WallClockDeadlineReached,
     StackFrameLimitReached,
-    AlignmentCheckFailed {
+    UnalignedRead {
+        required: usize,
+        has: usize,
+    },
+    UnalignedWrite {
         required: usize,
         has: usize,
     },
@@ -45,11 +78,57 @@ pub enum EvalError<'tcx> {
     VtableForArgumentlessMethod,
     ModifiedConstantMemory,
     AssumptionNotHeld,
+    Unreachable,
     InlineAsm,
+    NotConst(String),
+    StrictProvenanceViolation,
+    ArgumentCountMismatch {
+        expected: usize,
+        got: usize,
+    },
 }
 
 pub type EvalResult<'tcx, T> = Result<T, EvalError<'tcx>>;
 
+impl<'tcx> EvalError<'tcx> {
+    /// Whether this error means the program itself has undefined behavior, as opposed to miri
+    /// not supporting something or a configured limit being reached.
+    pub fn is_undefined_behavior(&self) -> bool {
+        use self::EvalError::*;
+        match *self {
+            FunctionPointerTyMismatch(..) |
+            DanglingPointerDeref(_) |
+            DeallocateNonBasePtr |
+            ReallocateNonBasePtr |
+            DoubleFree(_) |
+            InvalidMemoryAccess |
+            InvalidFunctionPointer |
+            InvalidBool |
+            InvalidDiscriminant |
+            PointerOutOfBounds { .. } |
+            ReadPointerAsBytes { .. } |
+            PartialPointerRead { .. } |
+            InvalidPointerMath |
+            PointerArithmeticOverflow |
+            ReadUndefBytes |
+            DerefFunctionPointer |
+            ExecuteMemory |
+            CopyNonOverlappingOverlaps |
+            InvalidChar(_) |
+            UncheckedDivisionByZero |
+            InexactDivision |
+            UnalignedRead { .. } |
+            UnalignedWrite { .. } |
+            CalledClosureAsFunction |
+            ModifiedConstantMemory |
+            AssumptionNotHeld |
+            Unreachable |
+            StrictProvenanceViolation => true,
+            _ => false,
+        }
+    }
+}
+
 impl<'tcx> Error for EvalError<'tcx> {
     fn description(&self) -> &str {
         match *self {
@@ -57,8 +136,14 @@ impl<'tcx> Error for EvalError<'tcx> {
                 "tried to call a function through a function pointer of a different type",
             EvalError::InvalidMemoryAccess =>
                 "tried to access memory through an invalid pointer",
-            EvalError::DanglingPointerDeref =>
+            EvalError::DanglingPointerDeref(_) =>
                 "dangling pointer was dereferenced",
+            EvalError::DeallocateNonBasePtr =>
+                "tried to deallocate with a pointer not to the beginning of an existing object",
+            EvalError::ReallocateNonBasePtr =>
+                "tried to reallocate with a pointer not to the beginning of an existing object",
+            EvalError::DoubleFree(_) =>
+                "tried to deallocate an allocation that was already deallocated",
             EvalError::InvalidFunctionPointer =>
                 "tried to use a pointer as a function pointer",
             EvalError::InvalidBool =>
@@ -67,10 +152,14 @@ impl<'tcx> Error for EvalError<'tcx> {
                 "invalid enum discriminant value read",
             EvalError::PointerOutOfBounds { .. } =>
                 "pointer offset outside bounds of allocation",
-            EvalError::ReadPointerAsBytes =>
+            EvalError::ReadPointerAsBytes { .. } =>
                 "a raw memory access tried to access part of a pointer value as raw bytes",
+            EvalError::PartialPointerRead { .. } =>
+                "tried to read a pointer from bytes that only partially overlap a pointer",
             EvalError::InvalidPointerMath =>
                 "attempted to do math or a comparison on pointers into different allocations",
+            EvalError::PointerArithmeticOverflow =>
+                "pointer arithmetic overflowed",
             EvalError::ReadUndefBytes =>
                 "attempted to read undefined bytes",
             EvalError::InvalidBoolOp(_) =>
@@ -80,22 +169,44 @@ impl<'tcx> Error for EvalError<'tcx> {
                 "tried to dereference a function pointer",
             EvalError::ExecuteMemory =>
                 "tried to treat a memory pointer as a function pointer",
+            EvalError::CopyNonOverlappingOverlaps =>
+                "copy_nonoverlapping called on overlapping ranges",
             EvalError::ArrayIndexOutOfBounds(..) =>
                 "array index out of bounds",
+            EvalError::SliceTooShort(..) =>
+                "slice shorter than the slice pattern",
             EvalError::Math(..) =>
                 "mathematical operation failed",
+            EvalError::DivisionByZero =>
+                "attempted to divide by zero or to calculate the remainder with a divisor of zero",
+            EvalError::UncheckedDivisionByZero =>
+                "called an unchecked division or remainder intrinsic with a divisor of zero",
+            EvalError::InexactDivision =>
+                "called `exact_div` on operands that don't divide evenly",
+            EvalError::OverflowingMath =>
+                "attempted a division or remainder that overflows",
             EvalError::NoMirFor(..) =>
                 "mir not found",
+            EvalError::NoSuchConstant(..) =>
+                "no constant with that path",
             EvalError::InvalidChar(..) =>
                 "tried to interpret an invalid 32-bit value as a char",
             EvalError::OutOfMemory{..} =>
                 "could not allocate more memory",
+            EvalError::AllocationTooLarge{..} =>
+                "tried to make a single allocation larger than the configured maximum",
+            EvalError::InvalidGrowSize{..} =>
+                "tried to grow an allocation to a smaller size",
             EvalError::ExecutionTimeLimitReached =>
                 "reached the configured maximum execution time",
+            EvalError::WallClockDeadlineReached =>
+                "reached the wall-clock time limit for running the program",
             EvalError::StackFrameLimitReached =>
                 "reached the configured maximum number of stack frames",
-            EvalError::AlignmentCheckFailed{..} =>
-                "tried to execute a misaligned read or write",
+            EvalError::UnalignedRead{..} =>
+                "tried to execute a misaligned read",
+            EvalError::UnalignedWrite{..} =>
+                "tried to execute a misaligned write",
             EvalError::CalledClosureAsFunction =>
                 "tried to call a closure through a function pointer",
             EvalError::VtableForArgumentlessMethod =>
@@ -104,8 +215,16 @@ impl<'tcx> Error for EvalError<'tcx> {
                 "tried to modify constant memory",
             EvalError::AssumptionNotHeld =>
                 "`assume` argument was false",
+            EvalError::Unreachable =>
+                "entered unreachable code",
             EvalError::InlineAsm =>
                 "cannot evaluate inline assembly",
+            EvalError::NotConst(_) =>
+                "tried to perform an operation that is not allowed in a constant",
+            EvalError::ArgumentCountMismatch { .. } =>
+                "tried to call a function with the wrong number of arguments",
+            EvalError::StrictProvenanceViolation =>
+                "tried to access memory through a pointer cast from an integer under strict provenance",
         }
     }
 
@@ -120,10 +239,13 @@ impl<'tcx> fmt::Display for EvalError<'tcx> {
                        ptr.offset, ptr.offset + size, ptr.alloc_id, allocation_size)
             },
             EvalError::NoMirFor(ref func) => write!(f, "no mir for `{}`", func),
+            EvalError::NoSuchConstant(ref path) => write!(f, "no constant `{}` in this crate", path),
             EvalError::FunctionPointerTyMismatch(expected, got) =>
                 write!(f, "tried to call a function of type {:?} through a function pointer of type {:?}", expected, got),
             EvalError::ArrayIndexOutOfBounds(span, len, index) =>
                 write!(f, "index out of bounds: the len is {} but the index is {} at {:?}", len, index, span),
+            EvalError::SliceTooShort(span, len, min_length) =>
+                write!(f, "slice pattern needs at least {} elements but the len is {} at {:?}", min_length, len, span),
             EvalError::Math(span, ref err) =>
                 write!(f, "{:?} at {:?}", err, span),
             EvalError::InvalidChar(c) =>
@@ -131,9 +253,31 @@ impl<'tcx> fmt::Display for EvalError<'tcx> {
             EvalError::OutOfMemory { allocation_size, memory_size, memory_usage } =>
                 write!(f, "tried to allocate {} more bytes, but only {} bytes are free of the {} byte memory",
                        allocation_size, memory_size - memory_usage, memory_size),
-            EvalError::AlignmentCheckFailed { required, has } =>
-               write!(f, "tried to access memory with alignment {}, but alignment {} is required",
+            EvalError::AllocationTooLarge { allocation_size, max_allocation_size } =>
+                write!(f, "tried to allocate {} bytes, but a single allocation may be at most {} bytes",
+                       allocation_size, max_allocation_size),
+            EvalError::InvalidGrowSize { allocation_size, new_size } =>
+                write!(f, "tried to grow an allocation of {} bytes to {} bytes", allocation_size, new_size),
+            EvalError::UnalignedRead { required, has } =>
+               write!(f, "tried to read memory with alignment {}, but alignment {} is required",
+                      has, required),
+            EvalError::UnalignedWrite { required, has } =>
+               write!(f, "tried to write memory with alignment {}, but alignment {} is required",
                       has, required),
+            EvalError::ArgumentCountMismatch { expected, got } =>
+                write!(f, "tried to call a function taking {} arguments with {} arguments", expected, got),
+            EvalError::DanglingPointerDeref(id) =>
+                write!(f, "dangling pointer was dereferenced: allocation {} has been deallocated", id),
+            EvalError::ReadPointerAsBytes { range: Some((ptr, size)) } =>
+                write!(f, "{}: bytes {}..{} of allocation {} overlap a pointer", self.description(),
+                       ptr.offset, ptr.offset + size, ptr.alloc_id),
+            EvalError::PartialPointerRead { ptr, pointer_start } =>
+                write!(f, "tried to read a pointer at byte {} of allocation {}, but there is a pointer at byte {}",
+                       ptr.offset, ptr.alloc_id, pointer_start),
+            EvalError::DoubleFree(id) =>
+                write!(f, "tried to deallocate allocation {} which was already deallocated", id),
+            EvalError::NotConst(ref what) =>
+                write!(f, "{} is not allowed in a constant", what),
             _ => write!(f, "{}", self.description()),
         }
     }
@@ -11,7 +11,7 @@ use rustc::ty::Ty;
 use syntax::ast::{FloatTy, IntTy, UintTy};
 
 impl<'a, 'tcx> EvalContext<'a, 'tcx> {
-    pub(super) fn cast_primval(&self, val: PrimVal, ty: Ty<'tcx>) -> EvalResult<'tcx, PrimVal> {
+    pub(super) fn cast_primval(&mut self, val: PrimVal, ty: Ty<'tcx>) -> EvalResult<'tcx, PrimVal> {
         use primval::PrimValKind::*;
         match val.kind {
             F32 => self.cast_float(val.to_f32() as f64, ty),
@@ -70,7 +70,7 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
             TyChar if v as u8 as u64 == v => Ok(PrimVal::new(v, Char)),
             TyChar => Err(EvalError::InvalidChar(v)),
 
-            TyRawPtr(_) => Ok(PrimVal::from_ptr(Pointer::from_int(v as usize))),
+            TyRawPtr(_) => Ok(PrimVal::from_ptr(self.memory.int_to_ptr(v))),
 
             _ => Err(EvalError::Unimplemented(format!("int to {:?} cast", ty))),
         }
@@ -91,22 +91,16 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
         }
     }
 
-    fn cast_ptr(&self, ptr: Pointer, ty: Ty<'tcx>) -> EvalResult<'tcx, PrimVal> {
-        use primval::PrimValKind::*;
+    fn cast_ptr(&mut self, ptr: Pointer, ty: Ty<'tcx>) -> EvalResult<'tcx, PrimVal> {
         use rustc::ty::TypeVariants::*;
         match ty.sty {
             TyRef(..) | TyRawPtr(_) => Ok(PrimVal::from_ptr(ptr)),
             TyFnPtr(_) => Ok(PrimVal::from_fn_ptr(ptr)),
 
-            TyInt(IntTy::I8)  => Ok(PrimVal::new(ptr.to_int()? as u64, I8)),
-            TyInt(IntTy::I16) => Ok(PrimVal::new(ptr.to_int()? as u64, I16)),
-            TyInt(IntTy::I32) => Ok(PrimVal::new(ptr.to_int()? as u64, I32)),
-            TyInt(IntTy::I64) => Ok(PrimVal::new(ptr.to_int()? as u64, I64)),
-
-            TyUint(UintTy::U8)  => Ok(PrimVal::new(ptr.to_int()? as u64, U8)),
-            TyUint(UintTy::U16) => Ok(PrimVal::new(ptr.to_int()? as u64, U16)),
-            TyUint(UintTy::U32) => Ok(PrimVal::new(ptr.to_int()? as u64, U32)),
-            TyUint(UintTy::U64) => Ok(PrimVal::new(ptr.to_int()? as u64, U64)),
+            TyInt(_) | TyUint(_) => {
+                let n = self.memory.ptr_to_int(ptr)?;
+                self.cast_int(n, ty, false)
+            }
 
             _ => Err(EvalError::Unimplemented(format!("ptr to {:?} cast", ty))),
         }
@@ -1,4 +1,5 @@
 use rustc::middle::const_val::ConstVal;
+use rustc::hir;
 use rustc::hir::def_id::DefId;
 use rustc::hir::map::definitions::DefPathData;
 use rustc::mir::mir_map::MirMap;
@@ -12,10 +13,11 @@ use rustc_data_structures::indexed_vec::Idx;
 use std::cell::RefCell;
 use std::ops::Deref;
 use std::rc::Rc;
+use std::time::Instant;
 use syntax::codemap::{self, DUMMY_SP};
 
 use error::{EvalError, EvalResult};
-use memory::{Memory, Pointer};
+use memory::{AllocId, Memory, MemorySnapshot, Pointer};
 use primval::{self, PrimVal, PrimValKind};
 pub use self::value::Value;
 
@@ -48,6 +50,27 @@ pub struct EvalContext<'a, 'tcx: 'a> {
 
     /// The maximum number of stack frames allowed
     stack_limit: usize,
+
+    /// Output written by the interpreted program to stdout or stderr, which hasn't been taken yet.
+    output: String,
+
+    /// Whether we are evaluating in a constant context, where side effects such as heap allocation
+    /// or accessing mutable statics are forbidden.
+    const_eval: bool,
+
+    /// The frozen allocations backing string literals, so evaluating the same literal repeatedly
+    /// (e.g. when matching on strings in a loop) doesn't allocate every time.
+    literal_strs: HashMap<String, Pointer>,
+
+    /// The environment variables visible to the interpreted program, mapping each name to a
+    /// NUL-terminated copy of its value in interpreter memory.
+    env_vars: HashMap<Vec<u8>, Pointer>,
+
+    /// Replacements for calls to Rust functions, keyed by the callee's `item_path_str`.
+    intercepts: HashMap<String, Box<FnMut(&[Value]) -> Value + 'a>>,
+
+    /// The `DefId` of `std::io::_print`, once a call to it has been seen.
+    print_fn: Option<DefId>,
 }
 
 /// A stack frame.
@@ -95,6 +118,21 @@ pub struct Frame<'a, 'tcx: 'a> {
 
     /// The index of the currently evaluated statment.
     pub stmt: usize,
+
+    /// The drop glue left to run for the `Drop` terminator or `drop_in_place` call the frame is
+    /// executing, with the next step last. It waits here while a `Drop::drop` call runs.
+    drops: Vec<PendingDrop<'tcx>>,
+}
+
+/// A step of the drop glue of a value, see `EvalContext::run_drops`.
+#[derive(Copy, Clone, Debug)]
+enum PendingDrop<'tcx> {
+    /// Runs the `Drop` impl of the value, if any, and then drops its fields.
+    Value(Pointer, Ty<'tcx>),
+    /// Drops the fields of the value, whose `Drop` impl has already run.
+    Fields(Pointer, Ty<'tcx>),
+    /// Frees the contents of a box after they have been dropped.
+    Deallocate(Pointer),
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -162,6 +200,15 @@ impl<'tcx> Global<'tcx> {
     }
 }
 
+/// The state of an `EvalContext` at some point, created by `EvalContext::snapshot`.
+#[derive(Clone, Debug)]
+pub struct EvalSnapshot<'tcx> {
+    memory: MemorySnapshot,
+    globals: HashMap<GlobalId<'tcx>, Global<'tcx>>,
+    literal_strs: HashMap<String, Pointer>,
+    env_vars: HashMap<Vec<u8>, Pointer>,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub enum StackPopCleanup {
     /// The stackframe existed to compute the initial value of a static/constant, make sure it
@@ -171,10 +218,69 @@ pub enum StackPopCleanup {
     /// A regular stackframe added due to a function call will need to get forwarded to the next
     /// block
     Goto(mir::BasicBlock),
+    /// The stackframe of a `Drop::drop` call made by drop glue. The caller resumes its pending
+    /// drops and then goes to the block.
+    Drop(mir::BasicBlock),
     /// The main function and diverging functions have nowhere to return to
     None,
 }
 
+/// The value returned by the entry function, see `eval_main_with_result`.
+#[derive(Clone, Debug)]
+pub struct MainResult {
+    pub value: Value,
+    /// The value as its `Display` implementation prints it.
+    pub formatted: String,
+}
+
+/// How a call to `EvalContext::run_with_deadline` ended without an error.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RunStatus {
+    /// The stack is empty, so there is nothing left to evaluate.
+    Finished,
+}
+
+/// The first undefined behavior a program ran into, see `EvalContext::run_ub_check`.
+#[derive(Clone, Debug)]
+pub struct UbReport<'tcx> {
+    /// The error describing what kind of undefined behavior happened.
+    pub error: EvalError<'tcx>,
+
+    /// The memory the offending operation accessed, if the error names it.
+    pub location: Option<Pointer>,
+
+    /// The statement or terminator that was being evaluated.
+    pub span: codemap::Span,
+
+    /// The functions on the stack, innermost first.
+    pub backtrace: Vec<FrameInfo<'tcx>>,
+}
+
+/// A function on the stack and how far it got, see `EvalContext::backtrace`.
+#[derive(Clone, Debug)]
+pub struct FrameInfo<'tcx> {
+    /// The function this frame is evaluating.
+    pub def_id: DefId,
+
+    /// The type arguments the function was instantiated with.
+    pub substs: &'tcx Substs<'tcx>,
+
+    /// The path of the function, like `foo::bar`.
+    pub name: String,
+
+    /// The span of the call that pushed this frame.
+    pub span: codemap::Span,
+
+    /// The basic block the frame is currently in.
+    pub block: mir::BasicBlock,
+
+    /// The index of the next statement of `block`, or its length if the terminator is next.
+    pub stmt: usize,
+}
+
+/// How many steps `run_with_deadline` takes between two looks at the clock.
+const DEADLINE_CHECK_INTERVAL: u64 = 1000;
+
 impl<'a, 'tcx> EvalContext<'a, 'tcx> {
     pub fn new(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir_map: &'a MirMap<'tcx>, memory_size: usize, stack_limit: usize) -> Self {
         EvalContext {
@@ -185,9 +291,60 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
             globals: HashMap::new(),
             stack: Vec::new(),
             stack_limit: stack_limit,
+            output: String::new(),
+            const_eval: false,
+            literal_strs: HashMap::new(),
+            env_vars: HashMap::new(),
+            intercepts: HashMap::new(),
+            print_fn: None,
+        }
+    }
+
+    /// Prepares the context for an independent evaluation by throwing away everything the previous
+    /// one left behind: the memory, the call stack, evaluated constants and statics, and pending
+    /// output. The environment variables live in the memory, so they are cleared as well. The MIR
+    /// loaded from other crates stays cached.
+    pub fn reset(&mut self) {
+        self.memory.clear();
+        self.globals.clear();
+        self.stack.clear();
+        self.output.clear();
+        self.const_eval = false;
+        self.literal_strs.clear();
+        self.env_vars.clear();
+    }
+
+    /// Takes a snapshot of the memory, together with the evaluated constants and statics, string
+    /// literals and environment variables that point into it. The call stack isn't included, so
+    /// snapshots should be taken and restored between evaluations.
+    pub fn snapshot(&self) -> EvalSnapshot<'tcx> {
+        EvalSnapshot {
+            memory: self.memory.snapshot(),
+            globals: self.globals.clone(),
+            literal_strs: self.literal_strs.clone(),
+            env_vars: self.env_vars.clone(),
         }
     }
 
+    /// Returns to the state `snapshot` was taken in. Constants, statics and string literals first
+    /// used since then are evaluated again when the program uses them next.
+    pub fn restore(&mut self, snapshot: EvalSnapshot<'tcx>) {
+        self.memory.restore(snapshot.memory);
+        self.globals = snapshot.globals;
+        self.literal_strs = snapshot.literal_strs;
+        self.env_vars = snapshot.env_vars;
+    }
+
+    /// Makes every call to the Rust function with the path `name` (as printed by
+    /// `TyCtxt::item_path_str`, e.g. `module::helper`) call `f` with the evaluated arguments
+    /// instead, and return whatever `f` returns. This works for functions that have MIR, too.
+    /// Registering another closure for the same name replaces the previous one.
+    pub fn intercept<F>(&mut self, name: &str, f: F)
+        where F: FnMut(&[Value]) -> Value + 'a
+    {
+        self.intercepts.insert(name.to_owned(), Box::new(f));
+    }
+
     pub fn alloc_ptr(&mut self, ty: Ty<'tcx>) -> EvalResult<'tcx, Pointer> {
         let substs = self.substs();
         self.alloc_ptr_with_substs(ty, substs)
@@ -215,6 +372,39 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
         &self.stack
     }
 
+    /// The maximum number of frames the stack may hold before `StackFrameLimitReached` is raised.
+    pub fn stack_limit(&self) -> usize {
+        self.stack_limit
+    }
+
+    /// Changes the stack limit. Frames already on the stack are kept even if there are too many.
+    pub fn set_stack_limit(&mut self, stack_limit: usize) {
+        self.stack_limit = stack_limit;
+    }
+
+    /// The output the interpreted program has produced since the last call to `take_output`.
+    /// Writes to stdout and stderr both end up here, interleaved in the order they happened.
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    /// Returns the accumulated output and clears the buffer.
+    pub fn take_output(&mut self) -> String {
+        ::std::mem::replace(&mut self.output, String::new())
+    }
+
+    /// Returns the id the next allocation will get. Pass it to `leaked_since` to find the heap
+    /// allocations made after this point that are still live.
+    pub fn alloc_watermark(&self) -> AllocId {
+        self.memory.next_alloc_id()
+    }
+
+    /// Returns the live heap allocations created since `alloc_watermark` returned `watermark`,
+    /// ordered by id.
+    pub fn leaked_since(&self, watermark: AllocId) -> Vec<AllocId> {
+        self.memory.leaks().into_iter().filter(|id| id.0 >= watermark.0).collect()
+    }
+
     fn usize_primval(&self, n: u64) -> PrimVal {
         PrimVal::from_uint_with_size(n, self.memory.pointer_size())
     }
@@ -224,10 +414,16 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
     }
 
     fn str_to_value(&mut self, s: &str) -> EvalResult<'tcx, Value> {
-        // FIXME: cache these allocs
-        let ptr = self.memory.allocate(s.len(), 1)?;
-        self.memory.write_bytes(ptr, s.as_bytes())?;
-        self.memory.freeze(ptr.alloc_id)?;
+        let ptr = match self.literal_strs.get(s) {
+            Some(&ptr) => ptr,
+            None => {
+                let ptr = self.memory.allocate(s.len(), 1)?;
+                self.memory.write_bytes(ptr, s.as_bytes())?;
+                self.memory.freeze(ptr.alloc_id)?;
+                self.literal_strs.insert(s.to_owned(), ptr);
+                ptr
+            }
+        };
         Ok(Value::ByValPair(PrimVal::from_ptr(ptr), self.usize_primval(s.len() as u64)))
     }
 
@@ -314,6 +510,16 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
         }
     }
 
+    /// Returns the MIR the interpreter uses for `def_id` (that is, after `run_mir_passes` has been
+    /// applied), if it is a crate-local item or has already been loaded from another crate.
+    pub fn mir_for(&self, def_id: DefId) -> Option<CachedMir<'a, 'tcx>> {
+        if def_id.is_local() {
+            self.mir_map.map.get(&def_id).map(CachedMir::Ref)
+        } else {
+            self.mir_cache.borrow().get(&def_id).cloned().map(CachedMir::Owned)
+        }
+    }
+
     pub fn monomorphize_field_ty(&self, f: ty::FieldDef<'tcx>, substs: &'tcx Substs<'tcx>) -> Ty<'tcx> {
         let substituted = &f.ty(self.tcx, substs);
         self.tcx.normalize_associated_type(&substituted)
@@ -380,6 +586,7 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
             def_id: def_id,
             substs: substs,
             stmt: 0,
+            drops: Vec::new(),
         });
 
         if self.stack.len() > self.stack_limit {
@@ -406,9 +613,26 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                 bug!("StackPopCleanup::Freeze on: {:?}", frame.return_lvalue);
             },
             StackPopCleanup::Goto(target) => self.goto_block(target),
+            // Resumed below, once the locals of the `Drop::drop` call are gone.
+            StackPopCleanup::Drop(_) |
             StackPopCleanup::None => {},
         }
-        // TODO(solson): Deallocate local variables.
+        // The value of a static or constant may point into the locals of its initializer, but
+        // the locals of a function call are gone once it returns. Their drops were already
+        // executed by the `Drop` terminators in the MIR.
+        if let Lvalue::Global(_) = frame.return_lvalue {
+            return Ok(());
+        }
+        for local in &frame.locals {
+            if let Some(Value::ByRef(ptr)) = *local {
+                if !ptr.points_to_zst() {
+                    self.memory.deallocate(ptr)?;
+                }
+            }
+        }
+        if let StackPopCleanup::Drop(target) = frame.return_to_block {
+            self.run_drops(target)?;
+        }
         Ok(())
     }
 
@@ -510,6 +734,17 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                     Univariant { ref variant, .. } => {
                         let offsets = variant.offsets.iter().map(|s| s.bytes());
                         self.assign_fields(dest, offsets, operands)?;
+
+                        // Bytes not covered by any field are padding, which is undefined.
+                        let fields: Vec<_> = variant.offsets.iter().zip(operands)
+                            .map(|(offset, operand)| {
+                                let operand_ty = self.operand_ty(operand);
+                                (offset.bytes() as usize, self.type_size(operand_ty))
+                            })
+                            .collect();
+                        let size = self.type_size(dest_ty);
+                        let dest = self.force_allocation(dest)?.to_ptr();
+                        self.memory.mark_padding_undef(dest, size, &fields)?;
                     }
 
                     Array { .. } => {
@@ -521,6 +756,20 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                         self.assign_fields(dest, offsets, operands)?;
                     }
 
+                    UntaggedUnion { .. } => {
+                        // All fields of a union live at offset 0, and only one of them can be
+                        // initialized by an aggregate.
+                        assert_eq!(operands.len(), 1);
+                        self.assign_fields(dest, Some(0), operands)?;
+                    }
+
+                    Vector { .. } => {
+                        let (elem_ty, _) = self.simd_elem_ty_and_len(dest_ty);
+                        let elem_size = self.type_size(elem_ty) as u64;
+                        let offsets = (0..).map(|i| i * elem_size);
+                        self.assign_fields(dest, offsets, operands)?;
+                    }
+
                     General { discr, ref variants, .. } => {
                         if let mir::AggregateKind::Adt(adt_def, variant, _, _) = *kind {
                             let discr_val = adt_def.variants[variant].disr_val.to_u64_unchecked();
@@ -616,9 +865,15 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                 // FIXME(solson)
                 let dest = self.force_allocation(dest)?.to_ptr();
 
-                for i in 0..length {
-                    let elem_dest = dest.offset((i * elem_size) as isize);
-                    self.write_value_to_ptr(value, elem_dest, elem_ty)?;
+                match value {
+                    // Byte arrays such as `[0u8; 4096]` are filled in one go.
+                    Value::ByVal(primval) if elem_size == 1 && primval.relocation.is_none() => {
+                        self.memory.write_repeat(dest, primval.bits as u8, length)?;
+                    }
+                    _ => for i in 0..length {
+                        let elem_dest = dest.offset((i * elem_size) as isize);
+                        self.write_value_to_ptr(value, elem_dest, elem_ty)?;
+                    },
                 }
             }
 
@@ -647,7 +902,12 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
             }
 
             Box(ty) => {
-                let ptr = self.alloc_ptr(ty)?;
+                if self.const_eval {
+                    return Err(EvalError::NotConst("heap allocation".to_owned()));
+                }
+                let size = self.type_size(ty);
+                let align = self.type_align(ty);
+                let ptr = self.memory.allocate_heap(size, align)?;
                 self.write_primval(dest, PrimVal::from_ptr(ptr))?;
             }
 
@@ -784,6 +1044,7 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                 let bytes = field_index * self.memory.pointer_size();
                 Ok(Size::from_bytes(bytes as u64))
             }
+            UntaggedUnion { .. } => Ok(Size::from_bytes(0)),
             _ => {
                 let msg = format!("can't handle type: {:?}, with layout: {:?}", ty, layout);
                 Err(EvalError::Unimplemented(msg))
@@ -791,6 +1052,13 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
         }
     }
 
+    /// Returns the lane type and the number of lanes of a `#[repr(simd)]` vector type. The lanes
+    /// are laid out like the elements of an array.
+    fn simd_elem_ty_and_len(&self, ty: Ty<'tcx>) -> (Ty<'tcx>, u64) {
+        assert!(ty.is_simd(), "expected a simd type, got {:?}", ty);
+        (ty.simd_type(self.tcx), ty.simd_size(self.tcx) as u64)
+    }
+
     fn get_field_count(&self, ty: Ty<'tcx>) -> EvalResult<'tcx, usize> {
         let layout = self.type_layout(ty);
 
@@ -882,6 +1150,14 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
         }
     }
 
+    /// Whether `def_id` is a `static mut` of this crate.
+    fn is_static_mut(&self, def_id: DefId) -> bool {
+        match self.tcx.map.get_if_local(def_id) {
+            Some(hir::map::Node::NodeItem(&hir::Item { node: hir::ItemStatic(_, hir::MutMutable, _), .. })) => true,
+            _ => false,
+        }
+    }
+
     fn eval_lvalue(&mut self, mir_lvalue: &mir::Lvalue<'tcx>) -> EvalResult<'tcx, Lvalue<'tcx>> {
         use rustc::mir::repr::Lvalue::*;
         let lvalue = match *mir_lvalue {
@@ -895,6 +1171,9 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
             }
 
             Static(def_id) => {
+                if self.const_eval && self.is_static_mut(def_id) {
+                    return Err(EvalError::NotConst("accessing a mutable static".to_owned()));
+                }
                 let substs = subst::Substs::empty(self.tcx);
                 let cid = GlobalId {
                     def_id: def_id,
@@ -954,6 +1233,14 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                         nonnull.offsets[field]
                     }
 
+                    // All fields of a union overlap at the start of the union.
+                    UntaggedUnion { .. } => Size::from_bytes(0),
+
+                    Vector { .. } => {
+                        let lane_size = self.type_size(field_ty) as u64;
+                        Size::from_bytes(field as u64 * lane_size)
+                    }
+
                     _ => bug!("field access on non-product type: {:?}", base_layout),
                 };
 
@@ -1030,8 +1317,11 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                 let usize = self.tcx.types.usize;
                 let n = self.value_to_primval(n_ptr, usize)?
                     .expect_uint("Projection::Index expected usize");
-                assert!(n < len);
-                let ptr = base_ptr.offset(n as isize * elem_size as isize);
+                // The bounds checks rustc emits usually catch this first.
+                if n >= len {
+                    return Err(EvalError::ArrayIndexOutOfBounds(self.current_span(), len, n));
+                }
+                let ptr = base_ptr.checked_offset(n as isize * elem_size as isize)?;
                 (ptr, LvalueExtra::None)
             }
 
@@ -1042,7 +1332,9 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
 
                 let (elem_ty, n) = base.elem_ty_and_len(base_ty);
                 let elem_size = self.type_size(elem_ty);
-                assert!(n >= min_length as u64);
+                if n < min_length as u64 {
+                    return Err(EvalError::SliceTooShort(self.current_span(), n, min_length as u64));
+                }
 
                 let index = if from_end {
                     n as isize - offset as isize
@@ -1063,7 +1355,11 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                 let elem_size = self.type_size(elem_ty);
                 assert!((from as u64) <= n - (to as u64));
                 let ptr = base_ptr.offset(from as isize * elem_size as isize);
-                let extra = LvalueExtra::Length(n - to as u64 - from as u64);
+                // A subslice of an array is itself an array, which needs no length.
+                let extra = match base_ty.sty {
+                    ty::TyArray(..) => LvalueExtra::None,
+                    _ => LvalueExtra::Length(n - to as u64 - from as u64),
+                };
                 (ptr, extra)
             }
         };
@@ -1289,6 +1585,9 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
         }
     }
 
+    /// Writes the two halves of a `Value::ByValPair` to the first and second field of `ty`. The
+    /// field offsets are taken from the layout of `ty`, so the fields need not be adjacent (e.g.
+    /// the overflow flag of a `(u64, bool)` checked operation result lives at offset 8).
     fn write_pair_to_ptr(
         &mut self,
         a: PrimVal,
@@ -1379,9 +1678,9 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
         use syntax::ast::FloatTy;
 
         let val = match ty.sty {
-            ty::TyBool => PrimVal::from_bool(self.memory.read_bool(ptr)?),
+            ty::TyBool => self.memory.read_primval(ptr, PrimValKind::Bool)?,
             ty::TyChar => {
-                let c = self.memory.read_uint(ptr, 4)? as u32;
+                let c = self.memory.read_primval(ptr, PrimValKind::Char)?.bits as u32;
                 match ::std::char::from_u32(c) {
                     Some(ch) => PrimVal::from_char(ch),
                     None => return Err(EvalError::InvalidChar(c as u64)),
@@ -1397,8 +1696,7 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                     I64 => 8,
                     Is => self.memory.pointer_size(),
                 };
-                let n = self.memory.read_int(ptr, size)?;
-                PrimVal::from_int_with_size(n, size)
+                self.memory.read_primval(ptr, PrimValKind::from_int_size(size))?
             }
 
             ty::TyUint(uint_ty) => {
@@ -1410,17 +1708,16 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                     U64 => 8,
                     Us => self.memory.pointer_size(),
                 };
-                let n = self.memory.read_uint(ptr, size)?;
-                PrimVal::from_uint_with_size(n, size)
+                self.memory.read_primval(ptr, PrimValKind::from_uint_size(size))?
             }
 
-            ty::TyFloat(FloatTy::F32) => PrimVal::from_f32(self.memory.read_f32(ptr)?),
-            ty::TyFloat(FloatTy::F64) => PrimVal::from_f64(self.memory.read_f64(ptr)?),
+            ty::TyFloat(FloatTy::F32) => self.memory.read_primval(ptr, PrimValKind::F32)?,
+            ty::TyFloat(FloatTy::F64) => self.memory.read_primval(ptr, PrimValKind::F64)?,
 
             ty::TyFnDef(def_id, substs, fn_ty) => {
                 PrimVal::from_fn_ptr(self.memory.create_fn_ptr(def_id, substs, fn_ty))
             },
-            ty::TyFnPtr(_) => self.memory.read_ptr(ptr).map(PrimVal::from_fn_ptr)?,
+            ty::TyFnPtr(_) => self.memory.read_primval(ptr, PrimValKind::FnPtr)?,
             ty::TyBox(ty) |
             ty::TyRef(_, ty::TypeAndMut { ty, .. }) |
             ty::TyRawPtr(ty::TypeAndMut { ty, .. }) => {
@@ -1563,6 +1860,250 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
         Ok(())
     }
 
+    /// Evaluates a call to `def_id` with the given arguments to completion as if it happened in a
+    /// constant, like a call to a `const fn` in an array length, and returns a reference to the
+    /// result. While the call is running, operations that are not allowed in a constant context
+    /// are rejected with `EvalError::NotConst`. This checks what the function does when it runs,
+    /// so it works for functions that aren't declared `const fn`, too.
+    pub fn eval_const_fn_call(
+        &mut self,
+        def_id: DefId,
+        substs: &'tcx Substs<'tcx>,
+        args: &[Value],
+        step_limit: u64,
+    ) -> EvalResult<'tcx, Value> {
+        let mir = self.load_mir(def_id)?;
+        if args.len() != mir.arg_count {
+            return Err(EvalError::ArgumentCountMismatch { expected: mir.arg_count, got: args.len() });
+        }
+        let return_ptr = self.alloc_ptr_with_substs(mir.return_ty, substs)?;
+        let depth = self.stack.len();
+
+        self.push_stack_frame(
+            def_id,
+            mir.span,
+            mir.clone(),
+            substs,
+            Lvalue::from_ptr(return_ptr),
+            StackPopCleanup::None,
+        )?;
+
+        for (arg_local, &arg_val) in mir.args_iter().zip(args) {
+            let arg_ty = self.monomorphize(mir.local_decls[arg_local].ty, substs);
+            let result = self.eval_lvalue(&mir::Lvalue::Local(arg_local))
+                .and_then(|dest| self.write_value(arg_val, dest, arg_ty));
+            if let Err(e) = result {
+                self.stack.truncate(depth);
+                return Err(e);
+            }
+        }
+
+        self.run_const_to_depth(depth, step_limit)?;
+        Ok(Value::ByRef(return_ptr))
+    }
+
+    /// Evaluates the crate-local `const` item with the given path (as printed by
+    /// `TyCtxt::item_path_str`, e.g. `module::X`) and returns its value. Like every other constant,
+    /// the result is cached, so evaluating the same item again is free.
+    pub fn eval_const_by_path(&mut self, path: &str, step_limit: u64) -> EvalResult<'tcx, Value> {
+        let tcx = self.tcx;
+        let def_id = self.mir_map.map.keys().into_iter().find(|&def_id| {
+            match tcx.map.get_if_local(def_id) {
+                Some(hir::map::Node::NodeItem(&hir::Item { node: hir::ItemConst(..), .. })) =>
+                    tcx.item_path_str(def_id) == path,
+                _ => false,
+            }
+        });
+        let def_id = match def_id {
+            Some(def_id) => def_id,
+            None => return Err(EvalError::NoSuchConstant(path.to_owned())),
+        };
+
+        let substs = subst::Substs::empty(self.tcx);
+        let cid = GlobalId { def_id: def_id, substs: substs, promoted: None };
+        if let Some(val) = self.globals.get(&cid).and_then(|global| global.data) {
+            return Ok(val);
+        }
+
+        let mir = self.load_mir(def_id)?;
+        let depth = self.stack.len();
+        self.globals.insert(cid, Global::uninitialized(mir.return_ty));
+        let result = self.push_stack_frame(def_id, mir.span, mir, substs, Lvalue::Global(cid), StackPopCleanup::Freeze)
+            .and_then(|()| self.run_const_to_depth(depth, step_limit));
+        if let Err(e) = result {
+            // Don't leave a half-evaluated constant behind.
+            self.stack.truncate(depth);
+            self.globals.remove(&cid);
+            return Err(e);
+        }
+        Ok(self.globals[&cid].data.expect("constant was evaluated but has no value"))
+    }
+
+    /// Runs the frames above `depth` in a constant context until they have all returned. If that
+    /// fails, they are popped, so the stack is left the way it was before they were pushed.
+    fn run_const_to_depth(&mut self, depth: usize, step_limit: u64) -> EvalResult<'tcx, ()> {
+        let was_const_eval = self.const_eval;
+        self.const_eval = true;
+        let result = self.run_to_depth(depth, step_limit);
+        self.const_eval = was_const_eval;
+        if result.is_err() {
+            self.stack.truncate(depth);
+        }
+        result
+    }
+
+    /// Steps until the stack is back to `depth` frames, or fails after `step_limit` steps.
+    fn run_to_depth(&mut self, depth: usize, step_limit: u64) -> EvalResult<'tcx, ()> {
+        for _ in 0..step_limit {
+            if self.stack.len() == depth {
+                return Ok(());
+            }
+            self.step()?;
+        }
+        Err(EvalError::ExecutionTimeLimitReached)
+    }
+
+    /// Steps until the stack is empty or the wall-clock time passes `deadline`, in which case
+    /// `WallClockDeadlineReached` is returned. The clock is only checked every few steps, so the
+    /// deadline can be overshot slightly. Running out of time doesn't disturb the evaluation, which
+    /// can be continued by calling this again with a later deadline.
+    pub fn run_with_deadline(&mut self, deadline: Instant) -> EvalResult<'tcx, RunStatus> {
+        let mut steps = 0u64;
+        loop {
+            if steps % DEADLINE_CHECK_INTERVAL == 0 && Instant::now() >= deadline {
+                return Err(EvalError::WallClockDeadlineReached);
+            }
+            if !self.step()? {
+                return Ok(RunStatus::Finished);
+            }
+            steps += 1;
+        }
+    }
+
+    /// Steps until the stack is empty, turning the first error that is undefined behavior into a
+    /// `UbReport`. Returns `Ok(None)` if the program finished without any, and fails with
+    /// `ExecutionTimeLimitReached` if it is still running after `step_limit` steps.
+    pub fn run_ub_check(&mut self, step_limit: u64) -> EvalResult<'tcx, Option<UbReport<'tcx>>> {
+        for _ in 0..step_limit {
+            match self.step() {
+                Ok(true) => {}
+                Ok(false) => return Ok(None),
+                Err(e) => return self.ub_report(e).map(Some),
+            }
+        }
+        Err(EvalError::ExecutionTimeLimitReached)
+    }
+
+    /// Describes `e` at the current position of the program, or hands it back if it is not
+    /// undefined behavior.
+    pub fn ub_report(&self, e: EvalError<'tcx>) -> EvalResult<'tcx, UbReport<'tcx>> {
+        if !e.is_undefined_behavior() {
+            return Err(e);
+        }
+        let location = match e {
+            EvalError::PointerOutOfBounds { ptr, .. } |
+            EvalError::ReadPointerAsBytes { range: Some((ptr, _)) } |
+            EvalError::PartialPointerRead { ptr, .. } => Some(ptr),
+            EvalError::DoubleFree(id) => Some(Pointer::new(id, 0)),
+            _ => None,
+        };
+        Ok(UbReport {
+            error: e,
+            location: location,
+            span: self.current_span(),
+            backtrace: self.backtrace(),
+        })
+    }
+
+    /// Describes every frame on the stack, innermost first.
+    pub fn backtrace(&self) -> Vec<FrameInfo<'tcx>> {
+        self.stack.iter().rev().map(|frame| FrameInfo {
+            def_id: frame.def_id,
+            substs: frame.substs,
+            name: self.tcx.item_path_str(frame.def_id),
+            span: frame.span,
+            block: frame.block,
+            stmt: frame.stmt,
+        }).collect()
+    }
+
+    /// The span of the statement or terminator the innermost frame is about to evaluate.
+    pub fn current_span(&self) -> codemap::Span {
+        let frame = self.frame();
+        let block = &frame.mir.basic_blocks()[frame.block];
+        if frame.stmt < block.statements.len() {
+            block.statements[frame.stmt].source_info.span
+        } else {
+            block.terminator().source_info.span
+        }
+    }
+
+    /// Makes `env` the environment of the interpreted program, replacing any previous one.
+    ///
+    /// Programs can only see it through the `getenv` shim. `std::env::vars_os` and `args_os` read
+    /// state the native runtime sets up before `main`, which isn't shimmed, and no `envp` is passed
+    /// to `#[start]` functions.
+    pub fn set_env_vars(&mut self, env: &[(&str, &str)]) -> EvalResult<'tcx, ()> {
+        self.env_vars.clear();
+        for &(name, value) in env {
+            let ptr = self.memory.allocate_c_str(value.as_bytes())?;
+            self.env_vars.insert(name.as_bytes().to_owned(), ptr);
+        }
+        Ok(())
+    }
+
+    /// Allocates a NULL-terminated array of pointers to NUL-terminated copies of `args`, as C's
+    /// `main` expects for its `argv` argument.
+    pub fn allocate_argv(&mut self, args: &[&str]) -> EvalResult<'tcx, Pointer> {
+        let ptr_size = self.memory.pointer_size();
+        let argv = self.memory.allocate((args.len() + 1) * ptr_size, ptr_size)?;
+        for (i, arg) in args.iter().enumerate() {
+            let arg_ptr = self.memory.allocate_c_str(arg.as_bytes())?;
+            self.memory.write_ptr(argv.offset((i * ptr_size) as isize), arg_ptr)?;
+        }
+        self.memory.write_usize(argv.offset((args.len() * ptr_size) as isize), 0)?;
+        Ok(argv)
+    }
+
+    /// Pushes the frame of a call to `def_id`, which has to take no arguments like `main`, or the
+    /// `argc` and `argv` built from `args` like a `#[start]` function. Returns the allocation its
+    /// return value will be written to once `step` has run the call to completion.
+    pub fn push_main_frame(&mut self, def_id: DefId, args: &[&str]) -> EvalResult<'tcx, Pointer> {
+        let mir = self.load_mir(def_id)?;
+        let substs = subst::Substs::empty(self.tcx);
+        // `main` returns `()`, but a `#[start]` function returns an `isize` that needs a place to go.
+        let return_ptr = self.alloc_ptr_with_substs(mir.return_ty, substs)?;
+
+        self.push_stack_frame(
+            def_id,
+            mir.span,
+            mir.clone(),
+            substs,
+            Lvalue::from_ptr(return_ptr),
+            StackPopCleanup::None,
+        )?;
+
+        if mir.arg_count == 2 {
+            self.write_start_args(args)?;
+        }
+        Ok(return_ptr)
+    }
+
+    /// Writes `argc` and `argv` into the arguments of the current frame, which must belong to a
+    /// `#[start]` function.
+    fn write_start_args(&mut self, args: &[&str]) -> EvalResult<'tcx, ()> {
+        let argv = self.allocate_argv(args)?;
+        let arg_locals: Vec<_> = self.frame().mir.args_iter().collect();
+        let (argc_local, argv_local) = (arg_locals[0], arg_locals[1]);
+        let ptr_size = self.memory.pointer_size();
+
+        let argc_dest = self.eval_lvalue(&mir::Lvalue::Local(argc_local))?;
+        self.write_primval(argc_dest, PrimVal::from_int_with_size(args.len() as i64, ptr_size))?;
+        let argv_dest = self.eval_lvalue(&mir::Lvalue::Local(argv_local))?;
+        self.write_primval(argv_dest, PrimVal::from_ptr(argv))?;
+        Ok(())
+    }
+
     fn dump_local(&self, lvalue: Lvalue<'tcx>) {
         if let Lvalue::Local { frame, local } = lvalue {
             if let Some(val) = self.stack[frame].get_local(local) {
@@ -1640,49 +2181,165 @@ impl<'mir, 'tcx: 'mir> Deref for CachedMir<'mir, 'tcx> {
     }
 }
 
+/// How `eval_main` runs a program. The defaults are what `miri` uses for a crate without any
+/// `#![miri(...)]` attributes.
+#[derive(Clone, Debug)]
+pub struct EvalConfig {
+    /// Maximum number of bytes the program may allocate in total.
+    pub memory_size: usize,
+
+    /// Maximum number of bytes a single allocation may have, if limited.
+    pub max_alloc_size: Option<usize>,
+
+    /// Number of steps after which the evaluation is aborted.
+    pub step_limit: u64,
+
+    /// Maximum number of stack frames.
+    pub stack_limit: usize,
+
+    /// Whether pointers can be cast to integers, see `Memory::set_deterministic_addresses`.
+    pub deterministic_addresses: bool,
+
+    /// See `Memory::set_strict_provenance`. Implies that pointers can be cast to integers.
+    pub strict_provenance: bool,
+
+    /// See `Memory::set_check_uninit`.
+    pub check_uninit: bool,
+
+    /// Whether it is an error for the program to finish with live heap allocations.
+    pub check_leaks: bool,
+
+    /// The arguments passed to a `#[start]` function.
+    pub args: Vec<String>,
+
+    /// The environment variables of the program, as names and values.
+    pub env: Vec<(String, String)>,
+}
+
+impl Default for EvalConfig {
+    fn default() -> Self {
+        EvalConfig {
+            memory_size: 100 * 1024 * 1024,
+            max_alloc_size: None,
+            step_limit: 1000_000,
+            stack_limit: 100,
+            deterministic_addresses: false,
+            strict_provenance: false,
+            check_uninit: true,
+            check_leaks: false,
+            args: Vec::new(),
+            env: Vec::new(),
+        }
+    }
+}
+
 pub fn eval_main<'a, 'tcx: 'a>(
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
     mir_map: &'a MirMap<'tcx>,
     def_id: DefId,
-    memory_size: usize,
-    step_limit: u64,
-    stack_limit: usize,
+    config: &EvalConfig,
 ) {
+    eval_main_with_result(tcx, mir_map, def_id, config);
+}
+
+/// Like `eval_main`, but also returns the value the entry function returned, such as the `isize`
+/// of a `#[start]` function, together with how it prints. Returns `None` if it returns `()` or a
+/// value that lives in memory, which is gone once this returns, or if the evaluation failed, in
+/// which case the error has already been reported.
+pub fn eval_main_with_result<'a, 'tcx: 'a>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    mir_map: &'a MirMap<'tcx>,
+    def_id: DefId,
+    config: &EvalConfig,
+) -> Option<MainResult> {
     let mir = mir_map.map.get(&def_id).expect("no mir for main function");
-    let mut ecx = EvalContext::new(tcx, mir_map, memory_size, stack_limit);
-
-    ecx.push_stack_frame(
-        def_id,
-        mir.span,
-        CachedMir::Ref(mir),
-        subst::Substs::empty(tcx),
-        Lvalue::from_ptr(Pointer::zst_ptr()),
-        StackPopCleanup::None
-    ).expect("could not allocate first stack frame");
-
-    for _ in 0..step_limit {
+    let mut ecx = EvalContext::new(tcx, mir_map, config.memory_size, config.stack_limit);
+    ecx.memory_mut().set_max_alloc_size(config.max_alloc_size);
+    ecx.memory_mut().set_deterministic_addresses(config.deterministic_addresses);
+    ecx.memory_mut().set_strict_provenance(config.strict_provenance);
+    ecx.memory_mut().set_check_uninit(config.check_uninit);
+    let env: Vec<(&str, &str)> = config.env.iter().map(|&(ref name, ref value)| (&name[..], &value[..])).collect();
+    ecx.set_env_vars(&env).expect("could not allocate the environment");
+
+    let args: Vec<&str> = config.args.iter().map(|arg| &arg[..]).collect();
+    let return_ptr = ecx.push_main_frame(def_id, &args).expect("could not allocate first stack frame");
+
+    for _ in 0..config.step_limit {
         match ecx.step() {
             Ok(true) => {}
-            Ok(false) => return,
+            Ok(false) => {
+                print!("{}", ecx.take_output());
+                if config.check_leaks {
+                    report_leaks(tcx, &ecx, mir.span);
+                }
+                if mir.return_ty.is_nil() {
+                    return None;
+                }
+                let result = ecx.read_value(return_ptr, mir.return_ty).and_then(|value| match value {
+                    Value::ByRef(_) => Ok(None),
+                    _ => {
+                        let formatted = ecx.format_value(value, mir.return_ty)?;
+                        Ok(Some(MainResult { value: value, formatted: formatted }))
+                    }
+                });
+                return match result {
+                    Ok(result) => result,
+                    Err(e) => {
+                        // The stack is empty now, so there is no frame to point the error at.
+                        tcx.sess.err(&e.to_string());
+                        None
+                    }
+                };
+            }
             Err(e) => {
-                report(tcx, &ecx, e);
-                return;
+                print!("{}", ecx.take_output());
+                match ecx.ub_report(e) {
+                    Ok(ub) => report_ub(tcx, &ub),
+                    Err(e) => report(tcx, &ecx, e),
+                }
+                return None;
             }
         }
     }
+    print!("{}", ecx.take_output());
     report(tcx, &ecx, EvalError::ExecutionTimeLimitReached);
+    None
+}
+
+/// Reports an error at `span` if the program left any heap allocations behind.
+fn report_leaks(tcx: TyCtxt, ecx: &EvalContext, span: codemap::Span) {
+    let leaks = ecx.memory().leaks();
+    if !leaks.is_empty() {
+        let ids: Vec<String> = leaks.iter().map(|id| id.to_string()).collect();
+        tcx.sess.span_err(span, &format!("the program leaked {} heap allocation(s): {}",
+                                         leaks.len(), ids.join(", ")));
+    }
+}
+
+fn report<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, ecx: &EvalContext<'a, 'tcx>, e: EvalError<'tcx>) {
+    report_with_backtrace(tcx, ecx.current_span(), &e.to_string(), None, &ecx.backtrace());
 }
 
-fn report(tcx: TyCtxt, ecx: &EvalContext, e: EvalError) {
-    let frame = ecx.stack().last().expect("stackframe was empty");
-    let block = &frame.mir.basic_blocks()[frame.block];
-    let span = if frame.stmt < block.statements.len() {
-        block.statements[frame.stmt].source_info.span
-    } else {
-        block.terminator().source_info.span
-    };
-    let mut err = tcx.sess.struct_span_err(span, &e.to_string());
-    for &Frame { def_id, substs, span, .. } in ecx.stack().iter().rev() {
+fn report_ub<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, ub: &UbReport<'tcx>) {
+    let note = ub.location.map(|ptr| {
+        format!("the offending access was at byte {} of allocation {}", ptr.offset, ptr.alloc_id)
+    });
+    report_with_backtrace(tcx, ub.span, &format!("undefined behavior: {}", ub.error), note,
+                          &ub.backtrace);
+}
+
+fn report_with_backtrace<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    span: codemap::Span,
+    msg: &str,
+    note: Option<String>,
+    backtrace: &[FrameInfo<'tcx>],
+) {
+    let mut err = tcx.sess.struct_span_err(span, msg);
+    if let Some(note) = note {
+        err.note(&note);
+    }
+    for &FrameInfo { def_id, substs, span, .. } in backtrace {
         if tcx.def_key(def_id).disambiguated_data.data == DefPathData::ClosureExpr {
             err.span_note(span, "inside call to closure");
             continue;
@@ -20,7 +20,12 @@ use syntax::codemap::Span;
 use std::rc::Rc;
 
 impl<'a, 'tcx> EvalContext<'a, 'tcx> {
+    /// Evaluates the next statement or terminator of the innermost frame, or, if it uses
+    /// constants that haven't been evaluated yet, pushes the frames that compute them instead.
     /// Returns true as long as there are more things to do.
+    ///
+    /// Between two calls, the frames and memory can be inspected through `stack` and `memory`;
+    /// every local holds the value the program has computed so far.
     pub fn step(&mut self) -> EvalResult<'tcx, bool> {
         if self.stack.is_empty() {
             return Ok(false);
@@ -74,7 +79,7 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
     }
 
     fn statement(&mut self, stmt: &mir::Statement<'tcx>) -> EvalResult<'tcx, ()> {
-        trace!("{:?}", stmt);
+        trace!("{:?}[{}]: {:?}", self.frame().block, self.frame().stmt, stmt);
 
         use rustc::mir::repr::StatementKind::*;
         match stmt.kind {
@@ -94,7 +99,8 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
     }
 
     fn terminator(&mut self, terminator: &mir::Terminator<'tcx>) -> EvalResult<'tcx, ()> {
-        trace!("{:?}", terminator.kind);
+        // The terminator comes right after the last statement of its block.
+        trace!("{:?}[{}]: {:?}", self.frame().block, self.frame().stmt, terminator.kind);
         self.eval_terminator(terminator)?;
         if !self.stack.is_empty() {
             trace!("// {:?}", self.frame().block);
@@ -0,0 +1,142 @@
+use rustc::ty::subst::Substs;
+use rustc::ty::{self, Ty};
+
+use error::{EvalError, EvalResult};
+use interpreter::value::Value;
+use interpreter::EvalContext;
+use memory::Pointer;
+
+impl<'a, 'tcx> EvalContext<'a, 'tcx> {
+    /// Renders the `fmt::Arguments` passed to `std::io::_print` without interpreting `core::fmt`.
+    ///
+    /// Only plain `{}` and `{:?}` placeholders are supported, and only for integers, `bool`,
+    /// `char` and `str` (behind any number of references). Anything else, including format
+    /// specs like `{:x}` or `{:5}`, is reported as unimplemented.
+    pub(super) fn format_arguments(&mut self, args: Value, args_ty: Ty<'tcx>) -> EvalResult<'tcx, String> {
+        let args_ptr = match args {
+            Value::ByRef(ptr) => ptr,
+            _ => return Err(EvalError::Unimplemented("fmt::Arguments not passed by reference".to_owned())),
+        };
+        let pieces_ptr = args_ptr.offset(self.get_field_offset(args_ty, 0)?.bytes() as isize);
+        let fmt_ptr = args_ptr.offset(self.get_field_offset(args_ty, 1)?.bytes() as isize);
+        let arguments_ptr = args_ptr.offset(self.get_field_offset(args_ty, 2)?.bytes() as isize);
+
+        // `fmt` is `None` unless some placeholder has a format spec.
+        match self.memory.read_usize(fmt_ptr) {
+            Ok(0) => {}
+            Ok(_) | Err(EvalError::ReadPointerAsBytes { .. }) => {
+                return Err(EvalError::Unimplemented("formatting with format specs".to_owned()));
+            }
+            Err(e) => return Err(e),
+        }
+
+        let pointer_size = self.memory.pointer_size();
+        let (pieces, num_pieces) = self.read_slice(pieces_ptr)?;
+        let (arguments, num_arguments) = self.read_slice(arguments_ptr)?;
+
+        // Pieces and arguments alternate, starting with a piece. There may be one piece more.
+        let mut out = String::new();
+        for i in 0..num_pieces {
+            let piece = pieces.offset((i * 2 * pointer_size) as isize);
+            let text = self.read_str(piece)?;
+            out.push_str(&text);
+            if i < num_arguments {
+                let argument = arguments.offset((i * 2 * pointer_size) as isize);
+                self.format_argument(argument, &mut out)?;
+            }
+        }
+        Ok(out)
+    }
+
+    /// Formats one `fmt::ArgumentV1`, a pointer to the value next to the `fmt` function of the
+    /// formatting trait implementation that should print it.
+    fn format_argument(&mut self, argument: Pointer, out: &mut String) -> EvalResult<'tcx, ()> {
+        let value = self.memory.read_ptr(argument)?;
+        let formatter = self.memory.read_ptr(argument.offset(self.memory.pointer_size() as isize))?;
+        let (def_id, substs, _) = self.memory.get_fn(formatter.alloc_id)?;
+
+        let trait_name = match self.tcx.trait_of_item(def_id) {
+            Some(trait_id) => self.tcx.item_name(trait_id).as_str(),
+            None => return Err(EvalError::Unimplemented(format!("formatting with {}", self.tcx.item_path_str(def_id)))),
+        };
+        let debug = match &trait_name[..] {
+            "Display" => false,
+            "Debug" => true,
+            _ => return Err(EvalError::Unimplemented(format!("formatting with the {} trait", trait_name))),
+        };
+
+        // The formatting functions take `&T`, so `value` points to the `T` in `T: Display`.
+        let mut ptr = value;
+        let mut ty = substs.type_at(0);
+        while let ty::TyRef(_, ref mt) = ty.sty {
+            if let ty::TyStr = mt.ty.sty {
+                break;
+            }
+            ptr = self.memory.read_ptr(ptr)?;
+            ty = mt.ty;
+        }
+
+        self.format_value_at(ptr, ty, debug, out)
+    }
+
+    /// Formats `value` like its `Display` implementation would. This supports the same types as
+    /// `{}` placeholders in printed text.
+    pub fn format_value(&mut self, value: Value, ty: Ty<'tcx>) -> EvalResult<'tcx, String> {
+        let mut out = String::new();
+        match value {
+            Value::ByRef(ptr) => self.format_value_at(ptr, ty, false, &mut out)?,
+            _ => {
+                let substs = Substs::empty(self.tcx);
+                let ptr = self.alloc_ptr_with_substs(ty, substs)?;
+                let result = self.write_value_to_ptr(value, ptr, ty)
+                    .and_then(|()| self.format_value_at(ptr, ty, false, &mut out));
+                self.memory.deallocate(ptr)?;
+                result?;
+            }
+        }
+        Ok(out)
+    }
+
+    /// Appends the value of type `ty` at `ptr` to `out`, using the `Debug` format if `debug` is set
+    /// and the `Display` format otherwise.
+    fn format_value_at(&self, ptr: Pointer, ty: Ty<'tcx>, debug: bool, out: &mut String) -> EvalResult<'tcx, ()> {
+        let size = self.type_size(ty);
+        match ty.sty {
+            ty::TyBool => out.push_str(if self.memory.read_bool(ptr)? { "true" } else { "false" }),
+            ty::TyInt(_) => out.push_str(&self.memory.read_int(ptr, size)?.to_string()),
+            ty::TyUint(_) => out.push_str(&self.memory.read_uint(ptr, size)?.to_string()),
+            ty::TyChar => {
+                let c = self.memory.read_uint(ptr, 4)? as u32;
+                match ::std::char::from_u32(c) {
+                    Some(c) if debug => out.push_str(&format!("{:?}", c)),
+                    Some(c) => out.push(c),
+                    None => return Err(EvalError::InvalidChar(c as u64)),
+                }
+            }
+            ty::TyRef(..) => {
+                let text = self.read_str(ptr)?;
+                if debug {
+                    out.push_str(&format!("{:?}", text));
+                } else {
+                    out.push_str(&text);
+                }
+            }
+            _ => return Err(EvalError::Unimplemented(format!("formatting values of type {:?}", ty))),
+        }
+        Ok(())
+    }
+
+    /// Reads the data pointer and length of the slice reference stored at `ptr`.
+    fn read_slice(&self, ptr: Pointer) -> EvalResult<'tcx, (Pointer, usize)> {
+        let data = self.memory.read_ptr(ptr)?;
+        let len = self.memory.read_usize(ptr.offset(self.memory.pointer_size() as isize))?;
+        Ok((data, len as usize))
+    }
+
+    /// Reads the `&str` stored at `ptr`.
+    fn read_str(&self, ptr: Pointer) -> EvalResult<'tcx, String> {
+        let (data, len) = self.read_slice(ptr)?;
+        let bytes = self.memory.read_bytes(data, len)?;
+        Ok(String::from_utf8_lossy(bytes).into_owned())
+    }
+}
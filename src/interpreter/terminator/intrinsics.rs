@@ -3,10 +3,11 @@ use rustc::mir::repr as mir;
 use rustc::ty::layout::Layout;
 use rustc::ty::subst::Substs;
 use rustc::ty::{self, Ty};
+use syntax::codemap::Span;
 
 use error::{EvalError, EvalResult};
 use interpreter::value::Value;
-use interpreter::{EvalContext, Lvalue};
+use interpreter::{EvalContext, Lvalue, PendingDrop};
 use primval::{self, PrimVal, PrimValKind};
 
 impl<'a, 'tcx> EvalContext<'a, 'tcx> {
@@ -18,12 +19,14 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
         dest: Lvalue<'tcx>,
         dest_ty: Ty<'tcx>,
         dest_layout: &'tcx Layout,
+        span: Span,
     ) -> EvalResult<'tcx, ()> {
         let arg_vals: EvalResult<Vec<Value>> = args.iter()
             .map(|arg| self.eval_operand(arg))
             .collect();
         let arg_vals = arg_vals?;
         let i32 = self.tcx.types.i32;
+        let u32 = self.tcx.types.u32;
         let isize = self.tcx.types.isize;
         let usize = self.tcx.types.usize;
         let f32 = self.tcx.types.f32;
@@ -69,11 +72,49 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                 self.write_value_to_ptr(arg_vals[1], dest, ty)?;
             }
 
+            // Miri is single threaded, so all orderings behave the same way.
+            name if name.starts_with("atomic_xchg") => {
+                let ty = substs.type_at(0);
+                let ptr = arg_vals[0].read_ptr(&self.memory)?;
+                let change = self.value_to_primval(arg_vals[1], ty)?;
+                let old = self.value_to_primval(Value::ByRef(ptr), ty)?;
+                self.write_primval(dest, old)?;
+                self.memory.write_primval(ptr, change)?;
+            }
+
+            name if name.starts_with("atomic_cxchg") => {
+                let ty = substs.type_at(0);
+                let ptr = arg_vals[0].read_ptr(&self.memory)?;
+                let expect_old = self.value_to_primval(arg_vals[1], ty)?;
+                let change = self.value_to_primval(arg_vals[2], ty)?;
+                let old = self.value_to_primval(Value::ByRef(ptr), ty)?;
+                let (success, _) = primval::binary_op(mir::BinOp::Eq, old, expect_old)?;
+                self.write_value(Value::ByValPair(old, success), dest, dest_ty)?;
+                if success.try_as_bool()? {
+                    self.memory.write_primval(ptr, change)?;
+                }
+            }
+
+            name if name.starts_with("atomic_xadd") || name.starts_with("atomic_xsub") => {
+                let ty = substs.type_at(0);
+                let ptr = arg_vals[0].read_ptr(&self.memory)?;
+                let change = self.value_to_primval(arg_vals[1], ty)?;
+                let old = self.value_to_primval(Value::ByRef(ptr), ty)?;
+                let op = if name.starts_with("atomic_xadd") {
+                    mir::BinOp::Add
+                } else {
+                    mir::BinOp::Sub
+                };
+                // Atomic arithmetic wraps around on overflow.
+                let (val, _) = primval::binary_op(op, old, change)?;
+                self.write_primval(dest, old)?;
+                self.memory.write_primval(ptr, val)?;
+            }
+
             "breakpoint" => unimplemented!(), // halt miri
 
             "copy" |
             "copy_nonoverlapping" => {
-                // FIXME: check whether overlapping occurs
                 let elem_ty = substs.type_at(0);
                 let elem_size = self.type_size(elem_ty);
                 let elem_align = self.type_align(elem_ty);
@@ -81,6 +122,42 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                 let dest = arg_vals[1].read_ptr(&self.memory)?;
                 let count = self.value_to_primval(arg_vals[2], usize)?
                     .expect_uint("arith_offset second arg not isize");
+                let size = count as usize * elem_size;
+                if intrinsic_name == "copy_nonoverlapping" {
+                    self.memory.check_nonoverlapping(src, dest, size)?;
+                }
+                self.memory.copy(src, dest, size, elem_align)?;
+            }
+
+            "swap_nonoverlapping" => {
+                let elem_ty = substs.type_at(0);
+                let elem_size = self.type_size(elem_ty);
+                let elem_align = self.type_align(elem_ty);
+                let a = arg_vals[0].read_ptr(&self.memory)?;
+                let b = arg_vals[1].read_ptr(&self.memory)?;
+                let count = self.value_to_primval(arg_vals[2], usize)?
+                    .expect_uint("swap_nonoverlapping third arg not usize");
+                let size = count as usize * elem_size;
+                self.memory.check_nonoverlapping(a, b, size)?;
+                // Going through a temporary allocation keeps the relocations and undefined bytes
+                // of both sides.
+                let tmp = self.memory.allocate(size, elem_align)?;
+                self.memory.copy(a, tmp, size, elem_align)?;
+                self.memory.copy(b, a, size, elem_align)?;
+                self.memory.copy(tmp, b, size, elem_align)?;
+                self.memory.deallocate(tmp)?;
+            }
+
+            // Unlike `copy`, these take the destination first.
+            "volatile_copy_memory" |
+            "volatile_copy_nonoverlapping_memory" => {
+                let elem_ty = substs.type_at(0);
+                let elem_size = self.type_size(elem_ty);
+                let elem_align = self.type_align(elem_ty);
+                let dest = arg_vals[0].read_ptr(&self.memory)?;
+                let src = arg_vals[1].read_ptr(&self.memory)?;
+                let count = self.value_to_primval(arg_vals[2], usize)?
+                    .expect_uint("volatile_copy_memory third arg not usize");
                 self.memory.copy(src, dest, count as usize * elem_size, elem_align)?;
             }
 
@@ -113,6 +190,29 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                 self.write_primval(dest, PrimVal::from_f64(f.abs()))?;
             }
 
+            "drop_in_place" => {
+                let ty = substs.type_at(0);
+                match ty.sty {
+                    ty::TySlice(elem_ty) => {
+                        let (ptr, len) = arg_vals[0].expect_slice(&self.memory)?;
+                        let elem_size = self.type_size(elem_ty);
+                        // Pushed in reverse, so the first element is dropped first.
+                        for i in (0..len as usize).rev() {
+                            let elem_ptr = ptr.offset((i * elem_size) as isize);
+                            self.frame_mut().drops.push(PendingDrop::Value(elem_ptr, elem_ty));
+                        }
+                    }
+                    ty::TyStr => {}
+                    ty::TyTrait(..) => {
+                        return Err(EvalError::Unimplemented("dropping trait objects in place".to_owned()));
+                    }
+                    _ => {
+                        let ptr = arg_vals[0].read_ptr(&self.memory)?;
+                        self.frame_mut().drops.push(PendingDrop::Value(ptr, ty));
+                    }
+                }
+            }
+
             "fadd_fast" => {
                 let ty = substs.type_at(0);
                 let a = self.value_to_primval(arg_vals[0], ty)?;
@@ -121,6 +221,23 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                 self.write_primval(dest, result.0)?;
             }
 
+            "write_bytes" |
+            "volatile_set_memory" => {
+                let u8 = self.tcx.types.u8;
+                let elem_ty = substs.type_at(0);
+                let elem_size = self.type_size(elem_ty);
+                let elem_align = self.type_align(elem_ty);
+                let ptr = arg_vals[0].read_ptr(&self.memory)?;
+                let val = self.value_to_primval(arg_vals[1], u8)?.expect_uint("write_bytes second arg not u8");
+                let count = self.value_to_primval(arg_vals[2], usize)?
+                    .expect_uint("write_bytes third arg not usize");
+                let size = count as usize * elem_size;
+                if size > 0 {
+                    self.memory.check_align_write(ptr, elem_align)?;
+                    self.memory.write_repeat(ptr, val as u8, size)?;
+                }
+            }
+
             "likely" |
             "unlikely" |
             "forget" => {}
@@ -133,6 +250,12 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                 self.memory.write_repeat(dest, 0, size)?;
             }
 
+            // Nothing is known at compile time in an interpreter, and answering `false` is always
+            // allowed, so the general code path runs.
+            "is_val_statically_known" => {
+                self.write_primval(dest, PrimVal::from_bool(false))?;
+            }
+
             "min_align_of" => {
                 let elem_ty = substs.type_at(0);
                 let elem_align = self.type_align(elem_ty);
@@ -140,6 +263,14 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                 self.write_primval(dest, align_val)?;
             }
 
+            "min_align_of_val" |
+            "min_align_of_val_raw" => {
+                let ty = substs.type_at(0);
+                let (_, align) = self.size_and_align_of_dst(ty, arg_vals[0])?;
+                let align_val = self.usize_primval(align);
+                self.write_primval(dest, align_val)?;
+            }
+
             "pref_align_of" => {
                 let ty = substs.type_at(0);
                 let layout = self.type_layout(ty);
@@ -168,7 +299,15 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                     .expect_int("offset second arg not isize");
 
                 let ptr = arg_vals[0].read_ptr(&self.memory)?;
-                let result_ptr = ptr.offset(offset as isize * pointee_size);
+                // Offsetting a pointer to a zero-sized type never changes its address, no matter
+                // how large the count is.
+                let result_ptr = if pointee_size == 0 {
+                    ptr
+                } else {
+                    let byte_offset = (offset as isize).checked_mul(pointee_size)
+                        .ok_or(EvalError::PointerArithmeticOverflow)?;
+                    ptr.checked_offset(byte_offset)?
+                };
                 self.write_primval(dest, PrimVal::from_ptr(result_ptr))?;
             }
 
@@ -184,6 +323,30 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                 self.intrinsic_overflowing(mir::BinOp::Add, &args[0], &args[1], dest)?;
             }
 
+            "unchecked_div" |
+            "exact_div" => {
+                let overflowed = self.intrinsic_overflowing(mir::BinOp::Div, &args[0], &args[1], dest)
+                    .map_err(unchecked_division_error)?;
+                if overflowed {
+                    return Err(EvalError::OverflowingMath);
+                }
+                if intrinsic_name == "exact_div" {
+                    let l = self.eval_operand_to_primval(&args[0])?;
+                    let r = self.eval_operand_to_primval(&args[1])?;
+                    if primval::binary_op(mir::BinOp::Rem, l, r)?.0.bits != 0 {
+                        return Err(EvalError::InexactDivision);
+                    }
+                }
+            }
+
+            "unchecked_rem" => {
+                let overflowed = self.intrinsic_overflowing(mir::BinOp::Rem, &args[0], &args[1], dest)
+                    .map_err(unchecked_division_error)?;
+                if overflowed {
+                    return Err(EvalError::OverflowingMath);
+                }
+            }
+
             "powif32" => {
                 let f = self.value_to_primval(arg_vals[0], f32)?
                     .expect_f32("powif32 first arg not f32");
@@ -212,6 +375,83 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                 self.write_primval(dest, PrimVal::from_f64(f.sqrt()))?;
             }
 
+            "raw_eq" => {
+                let ty = substs.type_at(0);
+                let size = self.type_size(ty);
+                let left = arg_vals[0].read_ptr(&self.memory)?;
+                let right = arg_vals[1].read_ptr(&self.memory)?;
+                let eq = self.memory.raw_eq(left, right, size)?;
+                self.write_primval(dest, PrimVal::from_bool(eq))?;
+            }
+
+            "simd_add" |
+            "simd_sub" |
+            "simd_mul" => {
+                let op = match intrinsic_name {
+                    "simd_add" => mir::BinOp::Add,
+                    "simd_sub" => mir::BinOp::Sub,
+                    "simd_mul" => mir::BinOp::Mul,
+                    _ => bug!("not a simd arithmetic intrinsic: {}", intrinsic_name),
+                };
+                let ty = substs.type_at(0);
+                let (elem_ty, len) = self.simd_elem_ty_and_len(ty);
+                let elem_size = self.type_size(elem_ty) as isize;
+                let left = match arg_vals[0] {
+                    Value::ByRef(ptr) => ptr,
+                    v => bug!("simd vector wasn't Value::ByRef: {:?}", v),
+                };
+                let right = match arg_vals[1] {
+                    Value::ByRef(ptr) => ptr,
+                    v => bug!("simd vector wasn't Value::ByRef: {:?}", v),
+                };
+
+                // FIXME(solson)
+                let dest = self.force_allocation(dest)?.to_ptr();
+
+                // Lanes are computed elementwise; integer lanes wrap around on overflow.
+                for i in 0..len as isize {
+                    let offset = i * elem_size;
+                    let l = self.value_to_primval(Value::ByRef(left.offset(offset)), elem_ty)?;
+                    let r = self.value_to_primval(Value::ByRef(right.offset(offset)), elem_ty)?;
+                    let (val, _) = primval::binary_op(op, l, r)?;
+                    self.memory.write_primval(dest.offset(offset), val)?;
+                }
+            }
+
+            "simd_extract" => {
+                let ty = substs.type_at(0);
+                let (elem_ty, len) = self.simd_elem_ty_and_len(ty);
+                let elem_size = self.type_size(elem_ty) as u64;
+                let vector = match arg_vals[0] {
+                    Value::ByRef(ptr) => ptr,
+                    v => bug!("simd vector wasn't Value::ByRef: {:?}", v),
+                };
+                let index = self.value_to_primval(arg_vals[1], u32)?
+                    .expect_uint("simd_extract second arg not u32");
+                if index >= len {
+                    return Err(EvalError::ArrayIndexOutOfBounds(span, len, index));
+                }
+                let lane = vector.offset((index * elem_size) as isize);
+                self.write_value(Value::ByRef(lane), dest, elem_ty)?;
+            }
+
+            "simd_insert" => {
+                let ty = substs.type_at(0);
+                let (elem_ty, len) = self.simd_elem_ty_and_len(ty);
+                let elem_size = self.type_size(elem_ty) as u64;
+                let index = self.value_to_primval(arg_vals[1], u32)?
+                    .expect_uint("simd_insert second arg not u32");
+                if index >= len {
+                    return Err(EvalError::ArrayIndexOutOfBounds(span, len, index));
+                }
+                self.write_value(arg_vals[0], dest, ty)?;
+
+                // FIXME(solson)
+                let dest = self.force_allocation(dest)?.to_ptr();
+                let lane = dest.offset((index * elem_size) as isize);
+                self.write_value_to_ptr(arg_vals[2], lane, elem_ty)?;
+            }
+
             "size_of" => {
                 let ty = substs.type_at(0);
                 let size = self.type_size(ty) as u64;
@@ -219,7 +459,8 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                 self.write_primval(dest, size_val)?;
             }
 
-            "size_of_val" => {
+            "size_of_val" |
+            "size_of_val_raw" => {
                 let ty = substs.type_at(0);
                 let (size, _) = self.size_and_align_of_dst(ty, arg_vals[0])?;
                 let size_val = self.usize_primval(size);
@@ -240,8 +481,29 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
             "transmute" => {
                 let dest_ty = substs.type_at(1);
                 let val = match arg_vals[0] {
-                    Value::ByVal(primval) =>
-                        Value::ByVal(self.transmute_primval(primval, dest_ty)?),
+                    Value::ByVal(primval) => {
+                        let primval = self.transmute_primval(primval, dest_ty)?;
+                        match primval.try_as_ptr() {
+                            // Only pointers keep their provenance. Any other type just gets the
+                            // address, which has to be transmuted back into an integer pointer.
+                            Some(ptr) if primval.kind != PrimValKind::Ptr && primval.kind != PrimValKind::FnPtr => {
+                                let addr = self.memory.ptr_to_int(ptr)?;
+                                Value::ByVal(PrimVal::new(addr, primval.kind))
+                            }
+                            _ => Value::ByVal(primval),
+                        }
+                    }
+                    // The same goes for a pointer stored in memory, which would otherwise be
+                    // copied along with its relocation.
+                    Value::ByRef(src) => match self.ty_to_primval_kind(dest_ty) {
+                        Ok(kind) if kind != PrimValKind::Ptr && kind != PrimValKind::FnPtr &&
+                                    !self.memory.relocations_in_range(src, self.type_size(dest_ty))?.is_empty() => {
+                            let ptr = self.memory.read_ptr(src)?;
+                            let addr = self.memory.ptr_to_int(ptr)?;
+                            Value::ByVal(PrimVal::new(addr, kind))
+                        }
+                        _ => Value::ByRef(src),
+                    },
                     v => v,
                 };
                 self.write_value(val, dest, dest_ty)?;
@@ -270,7 +532,6 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
         ty: ty::Ty<'tcx>,
         value: Value,
     ) -> EvalResult<'tcx, (u64, u64)> {
-        let pointer_size = self.memory.pointer_size();
         if self.type_is_sized(ty) {
             Ok((self.type_size(ty) as u64, self.type_align(ty) as u64))
         } else {
@@ -338,10 +599,7 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                 }
                 ty::TyTrait(..) => {
                     let (_, vtable) = value.expect_ptr_vtable_pair(&self.memory)?;
-                    // the second entry in the vtable is the dynamic size of the object.
-                    let size = self.memory.read_usize(vtable.offset(pointer_size as isize))?;
-                    let align = self.memory.read_usize(vtable.offset(pointer_size as isize * 2))?;
-                    Ok((size, align))
+                    self.read_size_and_align_from_vtable(vtable)
                 }
 
                 ty::TySlice(_) | ty::TyStr => {
@@ -396,3 +654,12 @@ fn numeric_intrinsic(name: &str, val: PrimVal) -> PrimVal {
         _       => bug!("not a numeric intrinsic: {}", name),
     }
 }
+
+/// Dividing by zero is undefined behavior for the unchecked division intrinsics, unlike for the
+/// division operators, which panic.
+fn unchecked_division_error(e: EvalError) -> EvalError {
+    match e {
+        EvalError::DivisionByZero => EvalError::UncheckedDivisionByZero,
+        e => e,
+    }
+}
@@ -5,6 +5,7 @@ use rustc::ty::fold::TypeFoldable;
 use rustc::ty::layout::Layout;
 use rustc::ty::subst::Substs;
 use rustc::ty::{self, Ty, TyCtxt, BareFnTy};
+use rustc_const_math::{ConstMathErr, Op};
 use std::rc::Rc;
 use syntax::codemap::{DUMMY_SP, Span};
 use syntax::{ast, attr};
@@ -12,9 +13,10 @@ use syntax::{ast, attr};
 use error::{EvalError, EvalResult};
 use memory::Pointer;
 use primval::PrimVal;
-use super::{EvalContext, Lvalue, IntegerExt, StackPopCleanup};
+use super::{EvalContext, Lvalue, IntegerExt, PendingDrop, StackPopCleanup};
 use super::value::Value;
 
+mod format;
 mod intrinsics;
 
 impl<'a, 'tcx> EvalContext<'a, 'tcx> {
@@ -88,10 +90,12 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                         let fn_ptr = self.eval_operand_to_primval(func)?
                             .expect_fn_ptr("TyFnPtr callee did not evaluate to FnPtr");
                         let (def_id, substs, fn_ty) = self.memory.get_fn(fn_ptr.alloc_id)?;
-                        if fn_ty != bare_fn_ty {
+                        // A different number of arguments is reported when they are passed to the
+                        // callee.
+                        if fn_ty != bare_fn_ty && fn_ty.sig.0.inputs.len() == bare_fn_ty.sig.0.inputs.len() {
                             return Err(EvalError::FunctionPointerTyMismatch(fn_ty, bare_fn_ty));
                         }
-                        self.eval_fn_call(def_id, substs, bare_fn_ty, destination, args,
+                        self.eval_fn_call(def_id, substs, fn_ty, destination, args,
                                           terminator.source_info.span)?
                     },
                     ty::TyFnDef(def_id, substs, fn_ty) => {
@@ -110,8 +114,8 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
 
                 let ptr = lvalue.to_ptr();
                 let ty = self.lvalue_ty(location);
-                self.drop(ptr, ty)?;
-                self.goto_block(target);
+                self.frame_mut().drops.push(PendingDrop::Value(ptr, ty));
+                self.run_drops(target)?;
             }
 
             Assert { ref cond, expected, ref msg, target, .. } => {
@@ -129,15 +133,24 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                                 .expect_uint("BoundsCheck index wasn't a uint");
                             Err(EvalError::ArrayIndexOutOfBounds(span, len, index))
                         },
+                        mir::AssertMessage::Math(ConstMathErr::DivisionByZero) |
+                        mir::AssertMessage::Math(ConstMathErr::RemainderByZero) =>
+                            Err(EvalError::DivisionByZero),
+                        mir::AssertMessage::Math(ConstMathErr::Overflow(Op::Div)) |
+                        mir::AssertMessage::Math(ConstMathErr::Overflow(Op::Rem)) =>
+                            Err(EvalError::OverflowingMath),
                         mir::AssertMessage::Math(ref err) =>
                             Err(EvalError::Math(terminator.source_info.span, err.clone())),
                     }
                 }
             },
 
-            DropAndReplace { .. } => unimplemented!(),
+            // Drop elaboration in `run_mir_passes` turns these into a `Drop` followed by an
+            // assignment, so they never reach the interpreter.
+            DropAndReplace { .. } => bug!("DropAndReplace should have been elaborated"),
+
             Resume => unimplemented!(),
-            Unreachable => unimplemented!(),
+            Unreachable => return Err(EvalError::Unreachable),
         }
 
         Ok(())
@@ -154,13 +167,19 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
     ) -> EvalResult<'tcx, ()> {
         use syntax::abi::Abi;
         match fn_ty.abi {
-            Abi::RustIntrinsic => {
+            Abi::RustIntrinsic | Abi::PlatformIntrinsic => {
+                let (ret, target) = match destination {
+                    Some(dest) => dest,
+                    None => return match &self.tcx.item_name(def_id).as_str()[..] {
+                        "unreachable" => Err(EvalError::Unreachable),
+                        name => Err(EvalError::Unimplemented(format!("can't handle diverging intrinsic: {}", name))),
+                    },
+                };
                 let ty = fn_ty.sig.0.output;
                 let layout = self.type_layout(ty);
-                let (ret, target) = destination.unwrap();
-                self.call_intrinsic(def_id, substs, arg_operands, ret, ty, layout)?;
-                self.goto_block(target);
-                Ok(())
+                self.call_intrinsic(def_id, substs, arg_operands, ret, ty, layout, span)?;
+                // `drop_in_place` only schedules the drops, so they have to run before moving on.
+                self.run_drops(target)
             }
 
             Abi::C => {
@@ -188,7 +207,40 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                         (def_id, substs)
                     };
 
+                // Building the path is expensive, so only do it if there is anything to look up.
+                let intercepted = if self.intercepts.is_empty() {
+                    None
+                } else {
+                    let path = self.tcx.item_path_str(resolved_def_id);
+                    self.intercepts.get_mut(&path).map(|f| {
+                        let arg_vals: Vec<Value> = args.iter().map(|&(val, _)| val).collect();
+                        f(&arg_vals)
+                    })
+                };
+                if let Some(result) = intercepted {
+                    // A diverging function can't return, so there is nowhere to continue.
+                    let (lvalue, target) = destination.ok_or(EvalError::Unreachable)?;
+                    self.write_value(result, lvalue, fn_ty.sig.0.output)?;
+                    self.goto_block(target);
+                    return Ok(());
+                }
+
+                // Printing goes through too much of `std::io` to interpret, so the formatted
+                // text is produced directly.
+                if self.is_print_fn(resolved_def_id) {
+                    let (arg, arg_ty) = args[0];
+                    let text = self.format_arguments(arg, arg_ty)?;
+                    self.output.push_str(&text);
+                    if let Some((_, target)) = destination {
+                        self.goto_block(target);
+                    }
+                    return Ok(());
+                }
+
                 let mir = self.load_mir(resolved_def_id)?;
+                if args.len() != mir.arg_count {
+                    return Err(EvalError::ArgumentCountMismatch { expected: mir.arg_count, got: args.len() });
+                }
                 let (return_lvalue, return_to_block) = match destination {
                     Some((lvalue, block)) => (lvalue, StackPopCleanup::Goto(block)),
                     None => {
@@ -251,7 +303,7 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
     fn read_nonnull_discriminant_value(&self, ptr: Pointer, nndiscr: u64) -> EvalResult<'tcx, u64> {
         let not_null = match self.memory.read_usize(ptr) {
             Ok(0) => false,
-            Ok(_) | Err(EvalError::ReadPointerAsBytes) => true,
+            Ok(_) | Err(EvalError::ReadPointerAsBytes { .. }) => true,
             Err(e) => return Err(e),
         };
         assert!(nndiscr == 0 || nndiscr == 1);
@@ -272,6 +324,10 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
             None => name.as_str(),
         };
 
+        if self.const_eval {
+            return Err(EvalError::NotConst(format!("calling C ABI function `{}`", link_name)));
+        }
+
         let args_res: EvalResult<Vec<Value>> = args.iter()
             .map(|arg| self.eval_operand(arg))
             .collect();
@@ -290,10 +346,18 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                     .expect_uint("__rust_allocate first arg not usize");
                 let align = self.value_to_primval(args[1], usize)?
                     .expect_uint("__rust_allocate second arg not usize");
-                let ptr = self.memory.allocate(size as usize, align as usize)?;
+                let ptr = self.memory.allocate_heap(size as usize, align as usize)?;
                 self.write_primval(dest, PrimVal::from_ptr(ptr))?;
             }
 
+            "__rust_deallocate" => {
+                let ptr = args[0].read_ptr(&self.memory)?;
+                // FIXME: insert sanity check for size and align?
+                let _old_size = self.value_to_primval(args[1], usize)?.expect_uint("__rust_deallocate second arg not usize");
+                let _align = self.value_to_primval(args[2], usize)?.expect_uint("__rust_deallocate third arg not usize");
+                self.memory.deallocate(ptr)?;
+            }
+
             "__rust_reallocate" => {
                 let ptr = args[0].read_ptr(&self.memory)?;
                 let size = self.value_to_primval(args[2], usize)?.expect_uint("__rust_reallocate third arg not usize");
@@ -305,7 +369,7 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
             "memcmp" => {
                 let left = args[0].read_ptr(&self.memory)?;
                 let right = args[1].read_ptr(&self.memory)?;
-                let n = self.value_to_primval(args[2], usize)?.expect_uint("__rust_reallocate first arg not usize") as usize;
+                let n = self.value_to_primval(args[2], usize)?.expect_uint("memcmp third arg not usize") as usize;
 
                 let result = {
                     let left_bytes = self.memory.read_bytes(left, n)?;
@@ -322,6 +386,45 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                 self.write_primval(dest, PrimVal::from_int_with_size(result, dest_size))?;
             }
 
+            // Lets tests snapshot the memory layout. Natively this is whatever the program defines.
+            "miri_dump_alloc" => {
+                let ptr = args[0].read_ptr(&self.memory)?;
+                for line in self.memory.dump_lines(ptr.alloc_id) {
+                    self.output.push_str(&line);
+                    self.output.push('\n');
+                }
+            }
+
+            "strlen" => {
+                let ptr = args[0].read_ptr(&self.memory)?;
+                let n = self.memory.read_c_str(ptr)?.len();
+                self.write_primval(dest, PrimVal::from_uint_with_size(n as u64, dest_size))?;
+            }
+
+            "write" => {
+                let i32 = self.tcx.types.i32;
+                let fd = self.value_to_primval(args[0], i32)?.expect_int("write first arg not i32");
+                let buf = args[1].read_ptr(&self.memory)?;
+                let n = self.value_to_primval(args[2], usize)?.expect_uint("write third arg not usize") as usize;
+
+                // Only stdout and stderr are supported; both end up in the same output buffer.
+                if fd != 1 && fd != 2 {
+                    return Err(EvalError::Unimplemented(format!("can't write to file descriptor {}", fd)));
+                }
+                let bytes = self.memory.read_bytes(buf, n)?;
+                self.output.push_str(&String::from_utf8_lossy(bytes));
+                self.write_primval(dest, PrimVal::from_int_with_size(n as i64, dest_size))?;
+            }
+
+            "getenv" => {
+                let name_ptr = args[0].read_ptr(&self.memory)?;
+                let value_ptr = {
+                    let name = self.memory.read_c_str(name_ptr)?;
+                    self.env_vars.get(name).cloned().unwrap_or_else(|| Pointer::from_int(0))
+                };
+                self.write_primval(dest, PrimVal::from_ptr(value_ptr))?;
+            }
+
             _ => {
                 return Err(EvalError::Unimplemented(format!("can't call C ABI function: {}", link_name)));
             }
@@ -452,9 +555,7 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
                 if let Some(&mut(ref mut first_arg, ref mut first_ty)) = args.get_mut(0) {
                     let (self_ptr, vtable) = first_arg.expect_ptr_vtable_pair(&self.memory)?;
                     *first_arg = Value::ByVal(PrimVal::from_ptr(self_ptr));
-                    let idx = idx + 3;
-                    let offset = idx * self.memory.pointer_size();
-                    let fn_ptr = self.memory.read_ptr(vtable.offset(offset as isize))?;
+                    let fn_ptr = self.read_method_from_vtable(vtable, idx)?;
                     let (def_id, substs, ty) = self.memory.get_fn(fn_ptr.alloc_id)?;
                     // FIXME: skip_binder is wrong for HKL
                     *first_ty = ty.sig.skip_binder().inputs[0];
@@ -467,33 +568,153 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
         }
     }
 
+    /// Whether `def_id` is `std::io::_print`. Comparing the name first avoids building the path
+    /// of every other function, and once the function has been found, only its id is compared.
+    fn is_print_fn(&mut self, def_id: DefId) -> bool {
+        if let Some(print_fn) = self.print_fn {
+            return def_id == print_fn;
+        }
+        let is_print_fn = match self.tcx.def_key(def_id).disambiguated_data.data.get_opt_name() {
+            Some(name) => name.as_str() == "_print" && self.tcx.item_path_str(def_id) == "std::io::_print",
+            None => false,
+        };
+        if is_print_fn {
+            self.print_fn = Some(def_id);
+        }
+        is_print_fn
+    }
+
     pub(super) fn type_needs_drop(&self, ty: Ty<'tcx>) -> bool {
         self.tcx.type_needs_drop_given_env(ty, &self.tcx.empty_parameter_environment())
     }
 
-    fn drop(&mut self, ptr: Pointer, ty: Ty<'tcx>) -> EvalResult<'tcx, ()> {
-        if !self.type_needs_drop(ty) {
-            debug!("no need to drop {:?}", ty);
-            return Ok(());
-        }
-        trace!("-need to drop {:?}", ty);
+    /// Runs the pending drops of the current frame and then continues at `target`. When a value
+    /// has a `Drop` impl, this stops after pushing the frame of the `Drop::drop` call, and popping
+    /// that frame resumes the remaining drops.
+    pub(super) fn run_drops(&mut self, target: mir::BasicBlock) -> EvalResult<'tcx, ()> {
+        while let Some(pending) = self.frame_mut().drops.pop() {
+            match pending {
+                PendingDrop::Value(ptr, ty) => {
+                    if !self.type_needs_drop(ty) {
+                        debug!("no need to drop {:?}", ty);
+                        continue;
+                    }
+                    trace!("-need to drop {:?}", ty);
+
+                    match ty.sty {
+                        ty::TyBox(contents_ty) => {
+                            let contents_ptr = self.memory.read_ptr(ptr)?;
+                            self.frame_mut().drops.push(PendingDrop::Deallocate(contents_ptr));
+                            self.frame_mut().drops.push(PendingDrop::Value(contents_ptr, contents_ty));
+                        }
 
-        // TODO(solson): Call user-defined Drop::drop impls.
+                        // The user-defined destructor runs first, then the fields of the active
+                        // variant are dropped.
+                        ty::TyAdt(adt_def, substs) => {
+                            self.frame_mut().drops.push(PendingDrop::Fields(ptr, ty));
+                            if let Some(drop_def_id) = adt_def.destructor() {
+                                return self.call_drop_impl(drop_def_id, substs, ptr, target);
+                            }
+                        }
 
-        match ty.sty {
-            ty::TyBox(_contents_ty) => {
-                let contents_ptr = self.memory.read_ptr(ptr)?;
-                // self.drop(contents_ptr, contents_ty)?;
-                trace!("-deallocating box");
-                self.memory.deallocate(contents_ptr)?;
-            }
+                        _ => self.frame_mut().drops.push(PendingDrop::Fields(ptr, ty)),
+                    }
+                }
 
-            // TODO(solson): Implement drop for other relevant types (e.g. aggregates).
-            _ => {}
+                PendingDrop::Fields(ptr, ty) => {
+                    // Pushed in reverse, so the first field is dropped first.
+                    for (field_ptr, field_ty) in self.drop_fields(ptr, ty)?.into_iter().rev() {
+                        self.frame_mut().drops.push(PendingDrop::Value(field_ptr, field_ty));
+                    }
+                }
+
+                PendingDrop::Deallocate(ptr) => {
+                    trace!("-deallocating box");
+                    self.memory.deallocate(ptr)?;
+                }
+            }
         }
 
+        self.goto_block(target);
         Ok(())
     }
+
+    /// The fields of the value of type `ty` at `ptr` that get dropped, in drop order.
+    fn drop_fields(&self, ptr: Pointer, ty: Ty<'tcx>) -> EvalResult<'tcx, Vec<(Pointer, Ty<'tcx>)>> {
+        let fields = match ty.sty {
+            ty::TyAdt(adt_def, substs) => {
+                let discr_val = self.read_discriminant_value(ptr, ty)?;
+                let variant_idx = adt_def.variants.iter()
+                    .position(|v| discr_val == v.disr_val.to_u64_unchecked())
+                    .ok_or(EvalError::InvalidDiscriminant)?;
+
+                use rustc::ty::layout::Layout::*;
+                let offsets: Vec<u64> = match *self.type_layout(ty) {
+                    Univariant { ref variant, .. } => variant.offsets.iter().map(|s| s.bytes()).collect(),
+                    // Skip the discriminant, which is field 0.
+                    General { ref variants, .. } =>
+                        variants[variant_idx].offsets[1..].iter().map(|s| s.bytes()).collect(),
+                    RawNullablePointer { nndiscr, .. } if variant_idx as u64 == nndiscr => vec![0],
+                    StructWrappedNullablePointer { nndiscr, ref nonnull, .. } if variant_idx as u64 == nndiscr =>
+                        nonnull.offsets.iter().map(|s| s.bytes()).collect(),
+                    // The other variants of nullable pointer enums have no fields, and union fields
+                    // are never dropped.
+                    _ => Vec::new(),
+                };
+
+                let fields = &adt_def.variants[variant_idx].fields;
+                fields.iter().zip(offsets)
+                    .map(|(field, offset)| (ptr.offset(offset as isize), field.ty(self.tcx, substs)))
+                    .collect()
+            }
+
+            ty::TyTuple(fields) => {
+                let offsets: Vec<u64> = match *self.type_layout(ty) {
+                    Layout::Univariant { ref variant, .. } => variant.offsets.iter().map(|s| s.bytes()).collect(),
+                    ref layout => bug!("tuple with non-univariant layout: {:?}", layout),
+                };
+                fields.iter().zip(offsets)
+                    .map(|(&field_ty, offset)| (ptr.offset(offset as isize), field_ty))
+                    .collect()
+            }
+
+            // Array elements are dropped in order, like the fields above.
+            ty::TyArray(elem_ty, len) => {
+                let elem_size = self.type_size(elem_ty);
+                (0..len).map(|i| (ptr.offset((i * elem_size) as isize), elem_ty)).collect()
+            }
+
+            // TODO(solson): Implement drop for other relevant types (e.g. slices and trait objects).
+            _ => Vec::new(),
+        };
+        Ok(fields)
+    }
+
+    /// Pushes the frame of the `Drop::drop` method `drop_def_id` for the value at `ptr`. A `Drop`
+    /// impl has exactly the generics of the type it is for, so `substs` are the type's substs.
+    fn call_drop_impl(
+        &mut self,
+        drop_def_id: DefId,
+        substs: &'tcx Substs<'tcx>,
+        ptr: Pointer,
+        target: mir::BasicBlock,
+    ) -> EvalResult<'tcx, ()> {
+        let mir = self.load_mir(drop_def_id)?;
+        let span = self.current_span();
+        self.push_stack_frame(
+            drop_def_id,
+            span,
+            mir,
+            substs,
+            Lvalue::from_ptr(Pointer::zst_ptr()),
+            StackPopCleanup::Drop(target),
+        )?;
+
+        let self_local = self.frame().mir.args_iter().next().expect("`Drop::drop` takes `&mut self`");
+        let self_ty = self.monomorphize(self.frame().mir.local_decls[self_local].ty, substs);
+        let self_lvalue = self.eval_lvalue(&mir::Lvalue::Local(self_local))?;
+        self.write_value(Value::ByVal(PrimVal::from_ptr(ptr)), self_lvalue, self_ty)
+    }
 }
 
 #[derive(Debug)]
@@ -56,6 +56,23 @@ impl<'a, 'tcx: 'a> Value {
         }
     }
 
+    pub(super) fn expect_slice(&self, mem: &Memory<'a, 'tcx>) -> EvalResult<'tcx, (Pointer, u64)> {
+        use self::Value::*;
+        match *self {
+            ByRef(ptr) => {
+                let data = mem.read_ptr(ptr)?;
+                let len = mem.read_usize(ptr.offset(mem.pointer_size() as isize))?;
+                Ok((data, len))
+            }
+
+            ByValPair(data, len) if data.try_as_ptr().is_some() && len.kind.is_int() => {
+                Ok((data.try_as_ptr().unwrap(), len.bits))
+            }
+
+            _ => bug!("expected slice, got {:?}", self),
+        }
+    }
+
     pub(super) fn expect_slice_len(&self, mem: &Memory<'a, 'tcx>) -> EvalResult<'tcx, u64> {
         use self::Value::*;
         match *self {
@@ -86,7 +86,7 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
 
         // FIXME: generate a destructor for the vtable.
         // trans does this with glue::get_drop_glue(ccx, trait_ref.self_ty())
-
+        self.memory.write_usize(vtable, 0)?;
         self.memory.write_usize(vtable.offset(ptr_size as isize), size as u64)?;
         self.memory.write_usize(vtable.offset((ptr_size * 2) as isize), align as u64)?;
 
@@ -101,6 +101,22 @@ impl<'a, 'tcx> EvalContext<'a, 'tcx> {
         Ok(vtable)
     }
 
+    /// Reads the size and alignment of the object's type from the header of `vtable`.
+    pub fn read_size_and_align_from_vtable(&self, vtable: Pointer) -> EvalResult<'tcx, (u64, u64)> {
+        let pointer_size = self.memory.pointer_size();
+        let size = self.memory.read_usize(vtable.offset(pointer_size as isize))?;
+        let align = self.memory.read_usize(vtable.offset(pointer_size as isize * 2))?;
+        Ok((size, align))
+    }
+
+    /// Reads the function pointer for the method with index `idx`, as computed by
+    /// `get_vtable_index_of_object_method`, from `vtable`. The methods follow the three entries
+    /// of the header: the drop glue, the size and the alignment.
+    pub fn read_method_from_vtable(&self, vtable: Pointer, idx: usize) -> EvalResult<'tcx, Pointer> {
+        let offset = (3 + idx) * self.memory.pointer_size();
+        self.memory.read_ptr(vtable.offset(offset as isize))
+    }
+
     fn get_vtable_methods(&mut self, impl_id: DefId, substs: &'tcx Substs<'tcx>) -> Vec<Option<ImplMethod<'tcx>>> {
         debug!("get_vtable_methods(impl_id={:?}, substs={:?}", impl_id, substs);
 
@@ -33,9 +33,16 @@ pub use interpreter::{
     CachedMir,
     EvalContext,
     Frame,
+    FrameInfo,
+    EvalConfig,
+    EvalSnapshot,
     eval_main,
+    eval_main_with_result,
+    MainResult,
     run_mir_passes,
+    RunStatus,
     StackPopCleanup,
+    UbReport,
     Value,
     Lvalue,
     LvalueExtra,
@@ -45,6 +52,10 @@ pub use memory::{
     Memory,
     Pointer,
     AllocId,
+    Allocation,
+    CompactStats,
+    MemorySnapshot,
+    Relocations,
 };
 
 pub use primval::{
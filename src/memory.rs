@@ -1,7 +1,7 @@
 use byteorder::{ReadBytesExt, WriteBytesExt, LittleEndian, BigEndian, self};
 use std::collections::Bound::{Included, Excluded};
 use std::collections::{btree_map, BTreeMap, HashMap, HashSet, VecDeque};
-use std::{fmt, iter, ptr};
+use std::{cmp, fmt, iter, ptr};
 
 use rustc::hir::def_id::DefId;
 use rustc::ty::{BareFnTy, ClosureTy, ClosureSubsts};
@@ -9,7 +9,7 @@ use rustc::ty::subst::Substs;
 use rustc::ty::layout::{self, TargetDataLayout};
 
 use error::{EvalError, EvalResult};
-use primval::PrimVal;
+use primval::{PrimVal, PrimValKind};
 
 ////////////////////////////////////////////////////////////////////////////////
 // Allocations and pointers
@@ -24,14 +24,14 @@ impl fmt::Display for AllocId {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Allocation {
     /// The actual bytes of the allocation.
     /// Note that the bytes of a pointer represent the offset of the pointer
     pub bytes: Vec<u8>,
     /// Maps from byte addresses to allocations.
     /// Only the first byte of a pointer is inserted into the map.
-    pub relocations: BTreeMap<usize, AllocId>,
+    relocations: BTreeMap<usize, AllocId>,
     /// Denotes undefined memory. Reading from undefined memory is forbidden in miri
     pub undef_mask: UndefMask,
     /// The alignment of the allocation to detect unaligned reads.
@@ -40,6 +40,40 @@ pub struct Allocation {
     /// Use the `freeze` method of `Memory` to ensure that an error occurs, if the memory of this
     /// allocation is modified in the future.
     pub immutable: bool,
+    /// Whether the program allocated this memory itself through `__rust_allocate` or `box`, as
+    /// opposed to the interpreter allocating it for locals, statics or literals. Only heap
+    /// allocations are expected to be freed by the program.
+    pub heap: bool,
+}
+
+impl Allocation {
+    /// Iterates over the pointers stored in this allocation as pairs of the offset of their first
+    /// byte and the allocation they point to, in order of their offsets.
+    pub fn relocations(&self) -> Relocations {
+        Relocations(self.relocations.iter())
+    }
+
+    /// Marks the bytes in `start..end` as undefined. If `zero` is set, their values are also
+    /// reset to zero, which is what reading them yields when undefined reads are allowed.
+    fn mark_undef(&mut self, start: usize, end: usize, zero: bool) {
+        self.undef_mask.set_range(start, end, false);
+        if zero {
+            for b in &mut self.bytes[start..end] {
+                *b = 0;
+            }
+        }
+    }
+}
+
+/// An iterator over the relocations of an `Allocation`, created by `Allocation::relocations`.
+pub struct Relocations<'a>(btree_map::Iter<'a, usize, AllocId>);
+
+impl<'a> Iterator for Relocations<'a> {
+    type Item = (usize, AllocId);
+
+    fn next(&mut self) -> Option<(usize, AllocId)> {
+        self.0.next().map(|(&offset, &alloc_id)| (offset, alloc_id))
+    }
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -58,6 +92,20 @@ impl Pointer {
         Pointer::new(self.alloc_id, new_offset)
     }
 
+    /// Like `offset`, but reports an error instead of wrapping around if the resulting offset
+    /// would be negative or not fit in a `usize`.
+    pub fn checked_offset<'tcx>(self, i: isize) -> EvalResult<'tcx, Self> {
+        let new_offset = if i >= 0 {
+            self.offset.checked_add(i as usize)
+        } else {
+            self.offset.checked_sub(i.wrapping_neg() as usize)
+        };
+        match new_offset {
+            Some(new_offset) => Ok(Pointer::new(self.alloc_id, new_offset)),
+            None => Err(EvalError::PointerArithmeticOverflow),
+        }
+    }
+
     pub fn points_to_zst(&self) -> bool {
         self.alloc_id == ZST_ALLOC_ID
     }
@@ -66,7 +114,7 @@ impl Pointer {
         match self.alloc_id {
             NEVER_ALLOC_ID |
             ZST_ALLOC_ID => Ok(self.offset),
-            _ => Err(EvalError::ReadPointerAsBytes),
+            _ => Err(EvalError::ReadPointerAsBytes { range: None }),
         }
     }
 
@@ -76,8 +124,10 @@ impl Pointer {
         Pointer::new(ZST_ALLOC_ID, i)
     }
 
+    /// The pointer handed out for zero-sized allocations. Like in rustc, it is a dangling but
+    /// non-null integer pointer, so it never compares equal to `ptr::null()`.
     pub fn zst_ptr() -> Self {
-        Pointer::new(ZST_ALLOC_ID, 0)
+        Pointer::new(ZST_ALLOC_ID, 1)
     }
 
     pub fn never_ptr() -> Self {
@@ -112,8 +162,12 @@ pub struct Memory<'a, 'tcx> {
     alloc_map: HashMap<AllocId, Allocation>,
     /// Number of virtual bytes allocated
     memory_usage: usize,
+    /// Number of allocations that have been deallocated so far
+    freed_allocations: usize,
     /// Maximum number of virtual bytes that may be allocated
     memory_size: usize,
+    /// Maximum number of virtual bytes a single allocation may have, if limited
+    max_alloc_size: Option<usize>,
     /// Function "allocations". They exist solely so pointers have something to point to, and
     /// we can figure out what they point to.
     functions: HashMap<AllocId, FunctionDefinition<'tcx>>,
@@ -121,11 +175,51 @@ pub struct Memory<'a, 'tcx> {
     function_alloc_cache: HashMap<FunctionDefinition<'tcx>, AllocId>,
     next_id: AllocId,
     pub layout: &'a TargetDataLayout,
+    /// Whether pointers into allocations may be cast to integers. If enabled, every allocation
+    /// whose address is observed gets a deterministic synthetic base address.
+    deterministic_addresses: bool,
+    /// The synthetic base addresses handed out so far.
+    base_addresses: HashMap<AllocId, u64>,
+    /// The lowest address that hasn't been handed out yet.
+    next_base_address: u64,
+    /// Whether casting an address back to a pointer is forbidden from recovering the provenance
+    /// of the allocation at that address.
+    strict_provenance: bool,
+    /// Whether reading undefined bytes is an error. If not, undefined bytes read as zero.
+    check_uninit: bool,
+}
+
+/// Statistics about the allocations of a `Memory`, created by `Memory::stats`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct CompactStats {
+    /// Number of allocations that haven't been deallocated
+    pub live_allocations: usize,
+    /// Number of allocations that have been deallocated
+    pub freed_allocations: usize,
+    /// Total size of all live allocations in bytes
+    pub live_bytes: usize,
+    /// Number of pointers stored in live allocations
+    pub relocations: usize,
+    /// The id the next allocation will get, which is one more than the largest id handed out
+    pub next_id: AllocId,
+}
+
+/// The contents of a `Memory` at some point, created by `Memory::snapshot`.
+#[derive(Clone, Debug)]
+pub struct MemorySnapshot {
+    alloc_map: HashMap<AllocId, Allocation>,
+    memory_usage: usize,
+    freed_allocations: usize,
+    base_addresses: HashMap<AllocId, u64>,
+    next_base_address: u64,
 }
 
 const ZST_ALLOC_ID: AllocId = AllocId(0);
 const NEVER_ALLOC_ID: AllocId = AllocId(1);
 
+/// The number of bytes per row in `Memory::dump`.
+const DUMP_ROW_LEN: usize = 16;
+
 impl<'a, 'tcx> Memory<'a, 'tcx> {
     pub fn new(layout: &'a TargetDataLayout, max_memory: usize) -> Self {
         Memory {
@@ -135,8 +229,151 @@ impl<'a, 'tcx> Memory<'a, 'tcx> {
             next_id: AllocId(2),
             layout: layout,
             memory_size: max_memory,
+            max_alloc_size: None,
             memory_usage: 0,
+            freed_allocations: 0,
+            deterministic_addresses: false,
+            base_addresses: HashMap::new(),
+            // Start above the null page, so no allocation ever gets a null address.
+            next_base_address: 0x1000,
+            strict_provenance: false,
+            check_uninit: true,
+        }
+    }
+
+    /// The id the next allocation will get. Ids only ever increase, so every allocation made from
+    /// now on gets an id at least this large.
+    pub fn next_alloc_id(&self) -> AllocId {
+        self.next_id
+    }
+
+    /// Returns the heap allocations that are still live, ordered by id. Once the program has
+    /// finished, these are the allocations it leaked.
+    pub fn leaks(&self) -> Vec<AllocId> {
+        let mut leaks: Vec<AllocId> = self.alloc_map.iter()
+            .filter(|&(_, alloc)| alloc.heap)
+            .map(|(&id, _)| id)
+            .collect();
+        leaks.sort_by_key(|id| id.0);
+        leaks
+    }
+
+    /// Frees every allocation and forgets all function pointers, as if the memory was newly
+    /// created. Configuration such as the memory size limits is kept.
+    pub fn clear(&mut self) {
+        self.alloc_map.clear();
+        self.functions.clear();
+        self.function_alloc_cache.clear();
+        self.next_id = AllocId(2);
+        self.memory_usage = 0;
+        self.freed_allocations = 0;
+        self.base_addresses.clear();
+        self.next_base_address = 0x1000;
+    }
+
+    pub fn set_deterministic_addresses(&mut self, enabled: bool) {
+        self.deterministic_addresses = enabled;
+    }
+
+    /// Configures whether integers cast back to pointers stay integer pointers, so that accessing
+    /// memory through them is an error. Pointers get addresses in this mode even without
+    /// deterministic addresses, so it's the access that fails instead of the cast.
+    pub fn set_strict_provenance(&mut self, enabled: bool) {
+        self.strict_provenance = enabled;
+    }
+
+    /// Configures whether reading undefined bytes is an error. This should be decided before
+    /// anything is allocated, because undefined bytes are only zeroed while it is disabled.
+    pub fn set_check_uninit(&mut self, enabled: bool) {
+        self.check_uninit = enabled;
+    }
+
+    /// Limits the size of any single allocation, independently of the total memory size.
+    pub fn set_max_alloc_size(&mut self, max_alloc_size: Option<usize>) {
+        self.max_alloc_size = max_alloc_size;
+    }
+
+    fn check_alloc_size(&self, size: usize) -> EvalResult<'tcx, ()> {
+        match self.max_alloc_size {
+            Some(max) if size > max => Err(EvalError::AllocationTooLarge {
+                allocation_size: size,
+                max_allocation_size: max,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Summarizes the live allocations and how many have been freed. The ids of freed allocations
+    /// are never reused, so this only reports the churn and doesn't compact anything.
+    pub fn stats(&self) -> CompactStats {
+        CompactStats {
+            live_allocations: self.alloc_map.len(),
+            freed_allocations: self.freed_allocations,
+            live_bytes: self.memory_usage,
+            relocations: self.alloc_map.values().map(|alloc| alloc.relocations.len()).sum(),
+            next_id: self.next_id,
+        }
+    }
+
+    /// Computes a hash of the bytes and relocations of all live allocations, visited in order of
+    /// their ids. Equal memory states always produce the same checksum, independently of the host.
+    pub fn checksum(&self) -> u64 {
+        // 64-bit FNV-1a, which is simple and doesn't depend on the standard library's hasher.
+        fn hash_bytes(mut hash: u64, bytes: &[u8]) -> u64 {
+            for &b in bytes {
+                hash ^= b as u64;
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+            hash
         }
+        fn hash_u64(hash: u64, n: u64) -> u64 {
+            let bytes = [
+                n as u8, (n >> 8) as u8, (n >> 16) as u8, (n >> 24) as u8,
+                (n >> 32) as u8, (n >> 40) as u8, (n >> 48) as u8, (n >> 56) as u8,
+            ];
+            hash_bytes(hash, &bytes)
+        }
+
+        let mut ids: Vec<AllocId> = self.alloc_map.keys().cloned().collect();
+        ids.sort_by_key(|id| id.0);
+
+        let mut hash = 0xcbf29ce484222325;
+        for id in ids {
+            let alloc = &self.alloc_map[&id];
+            hash = hash_u64(hash, id.0);
+            hash = hash_u64(hash, alloc.bytes.len() as u64);
+            hash = hash_bytes(hash, &alloc.bytes);
+            for (offset, target) in alloc.relocations() {
+                hash = hash_u64(hash, offset as u64);
+                hash = hash_u64(hash, target.0);
+            }
+        }
+        hash
+    }
+
+    /// Copies the contents of all live allocations, so that `restore` can return to this state.
+    /// Every allocation is cloned, so a snapshot costs as much memory as the program uses.
+    pub fn snapshot(&self) -> MemorySnapshot {
+        MemorySnapshot {
+            alloc_map: self.alloc_map.clone(),
+            memory_usage: self.memory_usage,
+            freed_allocations: self.freed_allocations,
+            base_addresses: self.base_addresses.clone(),
+            next_base_address: self.next_base_address,
+        }
+    }
+
+    /// Puts the allocations back into the state they had when `snapshot` was taken. Allocations
+    /// freed since then are live again, and ones created since then are gone. Ids are never handed
+    /// out twice, even after a restore, so pointers to the latter dangle instead of pointing to an
+    /// unrelated allocation. Function pointers stay valid either way. Pointers kept outside the
+    /// memory aren't touched, so an interpreter should use `EvalContext::restore` instead.
+    pub fn restore(&mut self, snapshot: MemorySnapshot) {
+        self.alloc_map = snapshot.alloc_map;
+        self.memory_usage = snapshot.memory_usage;
+        self.freed_allocations = snapshot.freed_allocations;
+        self.base_addresses = snapshot.base_addresses;
+        self.next_base_address = snapshot.next_base_address;
     }
 
     pub fn allocations(&self) -> ::std::collections::hash_map::Iter<AllocId, Allocation> {
@@ -180,6 +417,7 @@ impl<'a, 'tcx> Memory<'a, 'tcx> {
             return Ok(Pointer::zst_ptr());
         }
         assert!(align != 0);
+        self.check_alloc_size(size)?;
 
         if self.memory_size - self.memory_usage < size {
             return Err(EvalError::OutOfMemory {
@@ -195,6 +433,7 @@ impl<'a, 'tcx> Memory<'a, 'tcx> {
             undef_mask: UndefMask::new(size),
             align: align,
             immutable: false,
+            heap: false,
         };
         let id = self.next_id;
         self.next_id.0 += 1;
@@ -202,25 +441,30 @@ impl<'a, 'tcx> Memory<'a, 'tcx> {
         Ok(Pointer::new(id, 0))
     }
 
+    /// Like `allocate`, but marks the allocation as one the program is responsible for freeing.
+    pub fn allocate_heap(&mut self, size: usize, align: usize) -> EvalResult<'tcx, Pointer> {
+        let ptr = self.allocate(size, align)?;
+        if !ptr.points_to_zst() {
+            self.get_mut(ptr.alloc_id)?.heap = true;
+        }
+        Ok(ptr)
+    }
+
     // TODO(solson): Track which allocations were returned from __rust_allocate and report an error
     // when reallocating/deallocating any others.
     pub fn reallocate(&mut self, ptr: Pointer, new_size: usize, align: usize) -> EvalResult<'tcx, Pointer> {
+        if ptr.points_to_zst() {
+            return self.allocate_heap(new_size, align);
+        }
         // TODO(solson): Report error about non-__rust_allocate'd pointer.
         if ptr.offset != 0 {
-            return Err(EvalError::Unimplemented(format!("bad pointer offset: {}", ptr.offset)));
-        }
-        if ptr.points_to_zst() {
-            return self.allocate(new_size, align);
+            return Err(EvalError::ReallocateNonBasePtr);
         }
 
         let size = self.get(ptr.alloc_id)?.bytes.len();
 
         if new_size > size {
-            let amount = new_size - size;
-            self.memory_usage += amount;
-            let alloc = self.get_mut(ptr.alloc_id)?;
-            alloc.bytes.extend(iter::repeat(0).take(amount));
-            alloc.undef_mask.grow(amount, false);
+            self.grow_allocation(ptr, new_size)?;
         } else if size > new_size {
             self.memory_usage -= size - new_size;
             self.clear_relocations(ptr.offset(new_size as isize), size - new_size)?;
@@ -233,29 +477,130 @@ impl<'a, 'tcx> Memory<'a, 'tcx> {
         Ok(Pointer::new(ptr.alloc_id, 0))
     }
 
+    /// Grows the allocation `ptr` points to in place to `new_size` bytes. The new bytes are
+    /// undefined. `ptr` must point to the start of the allocation, and `new_size` can't be smaller
+    /// than its current size.
+    pub fn grow_allocation(&mut self, ptr: Pointer, new_size: usize) -> EvalResult<'tcx, ()> {
+        if ptr.offset != 0 {
+            return Err(EvalError::ReallocateNonBasePtr);
+        }
+        let size = self.get(ptr.alloc_id)?.bytes.len();
+        if new_size < size {
+            return Err(EvalError::InvalidGrowSize { allocation_size: size, new_size: new_size });
+        }
+        self.check_alloc_size(new_size)?;
+
+        let amount = new_size - size;
+        if self.memory_size - self.memory_usage < amount {
+            return Err(EvalError::OutOfMemory {
+                allocation_size: amount,
+                memory_size: self.memory_size,
+                memory_usage: self.memory_usage,
+            });
+        }
+        self.memory_usage += amount;
+        let alloc = self.get_mut(ptr.alloc_id)?;
+        alloc.bytes.extend(iter::repeat(0).take(amount));
+        alloc.undef_mask.grow(amount, false);
+        Ok(())
+    }
+
     // TODO(solson): See comment on `reallocate`.
     pub fn deallocate(&mut self, ptr: Pointer) -> EvalResult<'tcx, ()> {
         if ptr.points_to_zst() {
             return Ok(());
         }
         if ptr.offset != 0 {
-            // TODO(solson): Report error about non-__rust_allocate'd pointer.
-            return Err(EvalError::Unimplemented(format!("bad pointer offset: {}", ptr.offset)));
+            return Err(EvalError::DeallocateNonBasePtr);
         }
 
-        if let Some(alloc) = self.alloc_map.remove(&ptr.alloc_id) {
-            self.memory_usage -= alloc.bytes.len();
-        } else {
-            debug!("deallocated a pointer twice: {}", ptr.alloc_id);
-            // TODO(solson): Report error about erroneous free. This is blocked on properly tracking
-            // already-dropped state since this if-statement is entered even in safe code without
-            // it.
+        match self.alloc_map.remove(&ptr.alloc_id) {
+            Some(alloc) => {
+                self.memory_usage -= alloc.bytes.len();
+                self.freed_allocations += 1;
+            }
+            // Ids are never reused, so a missing id that was handed out before and isn't a
+            // function has been deallocated already.
+            None if ptr.alloc_id != NEVER_ALLOC_ID && ptr.alloc_id.0 < self.next_id.0 &&
+                    !self.functions.contains_key(&ptr.alloc_id) => {
+                return Err(EvalError::DoubleFree(ptr.alloc_id));
+            }
+            None => return Err(EvalError::InvalidMemoryAccess),
         }
         debug!("deallocated : {}", ptr.alloc_id);
 
         Ok(())
     }
 
+    /// Turns a pointer into an integer address. Pointers into actual allocations only have an
+    /// address if deterministic addresses or strict provenance are enabled. The address marks the
+    /// allocation as exposed, so `int_to_ptr` can recover its provenance unless strict provenance
+    /// is enforced.
+    pub fn ptr_to_int(&mut self, ptr: Pointer) -> EvalResult<'tcx, u64> {
+        if let Ok(n) = ptr.to_int() {
+            return Ok(n as u64);
+        }
+        if !self.deterministic_addresses && !self.strict_provenance {
+            return Err(EvalError::ReadPointerAsBytes { range: None });
+        }
+        if let Some(&base) = self.base_addresses.get(&ptr.alloc_id) {
+            return Ok(base + ptr.offset as u64);
+        }
+
+        let (size, align) = match self.alloc_map.get(&ptr.alloc_id) {
+            Some(alloc) => (alloc.bytes.len() as u64, alloc.align as u64),
+            // Functions have no bytes, but still need an address distinct from everything else.
+            None if self.functions.contains_key(&ptr.alloc_id) => (1, 1),
+            None => return Err(EvalError::DanglingPointerDeref(ptr.alloc_id)),
+        };
+        let base = (self.next_base_address + align - 1) / align * align;
+        self.next_base_address = base + cmp::max(size, 1);
+        self.base_addresses.insert(ptr.alloc_id, base);
+        Ok(base + ptr.offset as u64)
+    }
+
+    /// Turns an integer address back into a pointer. Unless strict provenance is enforced, an
+    /// address inside an allocation whose address was observed before gets that allocation's
+    /// provenance back. Otherwise the result is an integer pointer.
+    pub fn int_to_ptr(&self, addr: u64) -> Pointer {
+        if !self.strict_provenance {
+            if let Some(ptr) = self.exposed_ptr_at(addr) {
+                return ptr;
+            }
+        }
+        Pointer::from_int(addr as usize)
+    }
+
+    /// Finds the live allocation with a base address that contains `addr`.
+    fn exposed_ptr_at(&self, addr: u64) -> Option<Pointer> {
+        self.resolve_synthetic_addr(addr).map(|(alloc_id, offset)| Pointer::new(alloc_id, offset))
+    }
+
+    /// Maps an address handed out by `ptr_to_int` back to the allocation and offset it points
+    /// into. Only live allocations whose address has been observed can be found.
+    pub fn resolve_synthetic_addr(&self, addr: u64) -> Option<(AllocId, usize)> {
+        for (&alloc_id, &base) in &self.base_addresses {
+            // Function pointers and freed allocations have no bytes to access.
+            let size = match self.alloc_map.get(&alloc_id) {
+                Some(alloc) => alloc.bytes.len() as u64,
+                None => continue,
+            };
+            if base <= addr && addr < base + size {
+                return Some((alloc_id, (addr - base) as usize));
+            }
+        }
+        None
+    }
+
+    /// In strict provenance mode, accessing memory through an integer pointer whose address lies
+    /// within an allocation means the pointer was round-tripped through an integer.
+    fn check_provenance(&self, ptr: Pointer) -> EvalResult<'tcx, ()> {
+        if self.strict_provenance && ptr.points_to_zst() && self.exposed_ptr_at(ptr.offset as u64).is_some() {
+            return Err(EvalError::StrictProvenanceViolation);
+        }
+        Ok(())
+    }
+
     pub fn pointer_size(&self) -> usize {
         self.layout.pointer_size.bytes() as usize
     }
@@ -264,21 +609,30 @@ impl<'a, 'tcx> Memory<'a, 'tcx> {
         self.layout.endian
     }
 
-    pub fn check_align(&self, ptr: Pointer, align: usize) -> EvalResult<'tcx, ()> {
+    /// Returns the alignment `ptr` actually has if it is less than `align`, or `None` if the
+    /// access is sufficiently aligned.
+    fn misalignment(&self, ptr: Pointer, align: usize) -> EvalResult<'tcx, Option<usize>> {
         let alloc = self.get(ptr.alloc_id)?;
         if alloc.align < align {
-            return Err(EvalError::AlignmentCheckFailed {
-                has: alloc.align,
-                required: align,
-            });
-        }
-        if ptr.offset % align == 0 {
-            Ok(())
+            Ok(Some(alloc.align))
+        } else if ptr.offset % align != 0 {
+            Ok(Some(ptr.offset % align))
         } else {
-            Err(EvalError::AlignmentCheckFailed {
-                has: ptr.offset % align,
-                required: align,
-            })
+            Ok(None)
+        }
+    }
+
+    pub fn check_align_read(&self, ptr: Pointer, align: usize) -> EvalResult<'tcx, ()> {
+        match self.misalignment(ptr, align)? {
+            Some(has) => Err(EvalError::UnalignedRead { has: has, required: align }),
+            None => Ok(()),
+        }
+    }
+
+    pub fn check_align_write(&self, ptr: Pointer, align: usize) -> EvalResult<'tcx, ()> {
+        match self.misalignment(ptr, align)? {
+            Some(has) => Err(EvalError::UnalignedWrite { has: has, required: align }),
+            None => Ok(()),
         }
     }
 }
@@ -291,7 +645,10 @@ impl<'a, 'tcx> Memory<'a, 'tcx> {
             None => match self.functions.get(&id) {
                 Some(_) => Err(EvalError::DerefFunctionPointer),
                 None if id == ZST_ALLOC_ID => Err(EvalError::InvalidMemoryAccess),
-                None => Err(EvalError::DanglingPointerDeref),
+                // Ids are handed out in order, so a missing id below `next_id` belonged to an
+                // allocation that has been freed since.
+                None if id.0 >= self.next_id.0 => Err(EvalError::InvalidMemoryAccess),
+                None => Err(EvalError::DanglingPointerDeref(id)),
             }
         }
     }
@@ -303,7 +660,8 @@ impl<'a, 'tcx> Memory<'a, 'tcx> {
             None => match self.functions.get(&id) {
                 Some(_) => Err(EvalError::DerefFunctionPointer),
                 None if id == ZST_ALLOC_ID => Err(EvalError::InvalidMemoryAccess),
-                None => Err(EvalError::DanglingPointerDeref),
+                None if id.0 >= self.next_id.0 => Err(EvalError::InvalidMemoryAccess),
+                None => Err(EvalError::DanglingPointerDeref(id)),
             }
         }
     }
@@ -344,65 +702,106 @@ impl<'a, 'tcx> Memory<'a, 'tcx> {
 
     /// Print an allocation and all allocations it points to, recursively.
     pub fn dump(&self, id: AllocId) {
-        use std::fmt::Write;
+        for line in self.dump_lines(id) {
+            debug!("{}", line);
+        }
+    }
+
+    /// Print every live allocation, in order of their ids.
+    pub fn dump_all(&self) {
+        let mut ids: Vec<AllocId> = self.alloc_map.keys().cloned().collect();
+        ids.sort_by_key(|id| id.0);
+        let mut lines = Vec::new();
+        for id in ids {
+            self.dump_alloc(id, &mut lines);
+        }
+        for line in lines {
+            debug!("{}", line);
+        }
+    }
+
+    /// The lines `dump` prints. Bytes are shown in hex rows of `DUMP_ROW_LEN`, undefined bytes as
+    /// `__`, and every relocation is underlined with the id of the allocation it points to.
+    pub fn dump_lines(&self, id: AllocId) -> Vec<String> {
+        let mut lines = Vec::new();
         let mut allocs_seen = HashSet::new();
         let mut allocs_to_print = VecDeque::new();
+        allocs_seen.insert(id);
         allocs_to_print.push_back(id);
 
         while let Some(id) = allocs_to_print.pop_front() {
-            allocs_seen.insert(id);
-            let mut msg = format!("Alloc {:<5} ", format!("{}:", id));
-            if id == ZST_ALLOC_ID {
-                trace!("{} zst allocation", msg);
-                continue;
+            for target_id in self.dump_alloc(id, &mut lines) {
+                if allocs_seen.insert(target_id) {
+                    allocs_to_print.push_back(target_id);
+                }
             }
-            let prefix_len = msg.len();
-            let mut relocations = vec![];
-
-            let alloc = match (self.alloc_map.get(&id), self.functions.get(&id)) {
-                (Some(a), None) => a,
-                (None, Some(_)) => {
-                    // FIXME: print function name
-                    trace!("{} function pointer", msg);
-                    continue;
-                },
-                (None, None) => {
-                    trace!("{} (deallocated)", msg);
-                    continue;
-                },
-                (Some(_), Some(_)) => bug!("miri invariant broken: an allocation id exists that points to both a function and a memory location"),
-            };
+        }
+        lines
+    }
 
-            for i in 0..alloc.bytes.len() {
-                if let Some(&target_id) = alloc.relocations.get(&i) {
-                    if !allocs_seen.contains(&target_id) {
-                        allocs_to_print.push_back(target_id);
-                    }
-                    relocations.push((i, target_id));
-                }
+    /// Renders a single allocation into `lines` and returns the ids of the allocations it points
+    /// to, in order of offset.
+    fn dump_alloc(&self, id: AllocId, lines: &mut Vec<String>) -> Vec<AllocId> {
+        use std::fmt::Write;
+        let prefix = format!("Alloc {:<5} ", format!("{}:", id));
+        if id == ZST_ALLOC_ID {
+            lines.push(format!("{} zst allocation", prefix));
+            return Vec::new();
+        }
+
+        let alloc = match (self.alloc_map.get(&id), self.functions.get(&id)) {
+            (Some(a), None) => a,
+            (None, Some(_)) => {
+                // FIXME: print function name
+                lines.push(format!("{} function pointer", prefix));
+                return Vec::new();
+            },
+            (None, None) => {
+                lines.push(format!("{} (deallocated)", prefix));
+                return Vec::new();
+            },
+            (Some(_), Some(_)) => bug!("miri invariant broken: an allocation id exists that points to both a function and a memory location"),
+        };
+
+        let indent = format!("{:1$}", "", prefix.len());
+        let pointer_size = self.pointer_size();
+        let relocation_width = (pointer_size - 1) * 3;
+        let len = alloc.bytes.len();
+        let num_rows = cmp::max(1, (len + DUMP_ROW_LEN - 1) / DUMP_ROW_LEN);
+        let mut targets = vec![];
+
+        for row in 0..num_rows {
+            let start = row * DUMP_ROW_LEN;
+            let end = cmp::min(start + DUMP_ROW_LEN, len);
+
+            let mut msg = if row == 0 { prefix.clone() } else { indent.clone() };
+            for i in start..end {
                 if alloc.undef_mask.is_range_defined(i, i + 1) {
                     write!(msg, "{:02x} ", alloc.bytes[i]).unwrap();
                 } else {
                     msg.push_str("__ ");
                 }
             }
-
-            let immutable = if alloc.immutable { " (immutable)" } else { "" };
-            trace!("{}({} bytes){}", msg, alloc.bytes.len(), immutable);
-
-            if !relocations.is_empty() {
-                msg.clear();
-                write!(msg, "{:1$}", "", prefix_len).unwrap(); // Print spaces.
-                let mut pos = 0;
-                let relocation_width = (self.pointer_size() - 1) * 3;
-                for (i, target_id) in relocations {
-                    write!(msg, "{:1$}", "", (i - pos) * 3).unwrap();
-                    write!(msg, "└{0:─^1$}┘ ", format!("({})", target_id), relocation_width).unwrap();
-                    pos = i + self.pointer_size();
-                }
-                trace!("{}", msg);
+            if row + 1 == num_rows {
+                let immutable = if alloc.immutable { " (immutable)" } else { "" };
+                write!(msg, "({} bytes){}", len, immutable).unwrap();
+            }
+            lines.push(msg);
+
+            // Relocations are drawn below the row they start in, even if they continue past it.
+            let mut msg = indent.clone();
+            let mut pos = start;
+            for (&i, &target_id) in alloc.relocations.range(Included(&start), Excluded(&end)) {
+                write!(msg, "{:1$}", "", (i - pos) * 3).unwrap();
+                write!(msg, "└{0:─^1$}┘ ", format!("({})", target_id), relocation_width).unwrap();
+                pos = i + pointer_size;
+                targets.push(target_id);
+            }
+            if pos != start {
+                lines.push(msg.trim_right().to_owned());
             }
         }
+        targets
     }
 }
 
@@ -412,6 +811,7 @@ impl<'a, 'tcx> Memory<'a, 'tcx> {
         if size == 0 {
             return Ok(&[]);
         }
+        self.check_provenance(ptr)?;
         let alloc = self.get(ptr.alloc_id)?;
         if ptr.offset + size > alloc.bytes.len() {
             return Err(EvalError::PointerOutOfBounds {
@@ -427,6 +827,7 @@ impl<'a, 'tcx> Memory<'a, 'tcx> {
         if size == 0 {
             return Ok(&mut []);
         }
+        self.check_provenance(ptr)?;
         let alloc = self.get_mut(ptr.alloc_id)?;
         if ptr.offset + size > alloc.bytes.len() {
             return Err(EvalError::PointerOutOfBounds {
@@ -438,13 +839,15 @@ impl<'a, 'tcx> Memory<'a, 'tcx> {
         Ok(&mut alloc.bytes[ptr.offset..ptr.offset + size])
     }
 
+    /// Zero-sized reads and writes always succeed, even through dangling or one-past-the-end
+    /// pointers, since zero-sized types are everywhere in MIR.
     fn get_bytes(&self, ptr: Pointer, size: usize, align: usize) -> EvalResult<'tcx, &[u8]> {
         if size == 0 {
             return Ok(&[]);
         }
-        self.check_align(ptr, align)?;
+        self.check_align_read(ptr, align)?;
         if self.relocations(ptr, size)?.count() != 0 {
-            return Err(EvalError::ReadPointerAsBytes);
+            return Err(EvalError::ReadPointerAsBytes { range: Some((ptr, size)) });
         }
         self.check_defined(ptr, size)?;
         self.get_bytes_unchecked(ptr, size)
@@ -454,7 +857,7 @@ impl<'a, 'tcx> Memory<'a, 'tcx> {
         if size == 0 {
             return Ok(&mut []);
         }
-        self.check_align(ptr, align)?;
+        self.check_align_write(ptr, align)?;
         self.clear_relocations(ptr, size)?;
         self.mark_definedness(ptr, size, true)?;
         self.get_bytes_unchecked_mut(ptr, size)
@@ -471,12 +874,20 @@ impl<'a, 'tcx> Memory<'a, 'tcx> {
         Ok(())
     }
 
+    /// Copies `size` bytes from `src` to `dest`, which may overlap, along with their definedness
+    /// and relocations. Copying zero bytes does nothing, so neither pointer has to be valid.
     pub fn copy(&mut self, src: Pointer, dest: Pointer, size: usize, align: usize) -> EvalResult<'tcx, ()> {
         if size == 0 {
             return Ok(());
         }
         self.check_relocation_edges(src, size)?;
 
+        // Writing to `dest` clears its relocations, including ones straddling the edges of the
+        // range, and marks its bytes as defined. Since `src` and `dest` may overlap, the state of
+        // `src` has to be saved first.
+        let relocations = self.relocations_in_range(src, size)?;
+        let definedness = self.definedness(src, size)?;
+
         let src_bytes = self.get_bytes_unchecked(src, size)?.as_ptr();
         let dest_bytes = self.get_bytes_mut(dest, size, align)?.as_mut_ptr();
 
@@ -491,28 +902,81 @@ impl<'a, 'tcx> Memory<'a, 'tcx> {
             }
         }
 
-        self.copy_undef_mask(src, dest, size)?;
-        self.copy_relocations(src, dest, size)?;
+        self.copy_undef_mask(dest, definedness)?;
+        self.copy_relocations(dest, relocations)?;
 
         Ok(())
     }
 
+    /// Checks that the ranges of `size` bytes at `a` and `b` don't overlap, as required by
+    /// `copy_nonoverlapping` and `swap_nonoverlapping`.
+    pub fn check_nonoverlapping(&self, a: Pointer, b: Pointer, size: usize) -> EvalResult<'tcx, ()> {
+        if size != 0 && a.alloc_id == b.alloc_id && !a.points_to_zst() {
+            let (low, high) = if a.offset <= b.offset { (a, b) } else { (b, a) };
+            if low.offset + size > high.offset {
+                return Err(EvalError::CopyNonOverlappingOverlaps);
+            }
+        }
+        Ok(())
+    }
+
     pub fn read_bytes(&self, ptr: Pointer, size: usize) -> EvalResult<'tcx, &[u8]> {
         self.get_bytes(ptr, size, 1)
     }
 
+    /// Compares the raw bytes of two ranges of `size` bytes. All bytes must be defined. Pointers
+    /// stored in the ranges are only considered equal if they are at the same offsets and point
+    /// into the same allocations.
+    pub fn raw_eq(&self, left: Pointer, right: Pointer, size: usize) -> EvalResult<'tcx, bool> {
+        if size == 0 {
+            return Ok(true);
+        }
+        self.check_relocation_edges(left, size)?;
+        self.check_relocation_edges(right, size)?;
+        self.check_defined(left, size)?;
+        self.check_defined(right, size)?;
+        if self.relocations_in_range(left, size)? != self.relocations_in_range(right, size)? {
+            return Err(EvalError::ReadPointerAsBytes { range: Some((left, size)) });
+        }
+        Ok(self.get_bytes_unchecked(left, size)? == self.get_bytes_unchecked(right, size)?)
+    }
+
     pub fn write_bytes(&mut self, ptr: Pointer, src: &[u8]) -> EvalResult<'tcx, ()> {
         let bytes = self.get_bytes_mut(ptr, src.len(), 1)?;
         bytes.clone_from_slice(src);
         Ok(())
     }
 
+    /// Sets `count` bytes starting at `ptr` to `val`, like `memset`. The bytes become defined and
+    /// any pointer overlapping them is removed.
     pub fn write_repeat(&mut self, ptr: Pointer, val: u8, count: usize) -> EvalResult<'tcx, ()> {
         let bytes = self.get_bytes_mut(ptr, count, 1)?;
         for b in bytes { *b = val; }
         Ok(())
     }
 
+    /// Reads the NUL-terminated string starting at `ptr`, not including the terminator.
+    pub fn read_c_str(&self, ptr: Pointer) -> EvalResult<'tcx, &[u8]> {
+        let alloc = self.get(ptr.alloc_id)?;
+        let nul = alloc.bytes.get(ptr.offset..).and_then(|bytes| bytes.iter().position(|&b| b == 0));
+        match nul {
+            Some(len) => Ok(&self.get_bytes(ptr, len + 1, 1)?[..len]),
+            None => Err(EvalError::PointerOutOfBounds {
+                ptr: ptr,
+                size: alloc.bytes.len().saturating_sub(ptr.offset) + 1,
+                allocation_size: alloc.bytes.len(),
+            }),
+        }
+    }
+
+    /// Allocates a copy of `s` with a NUL terminator appended.
+    pub fn allocate_c_str(&mut self, s: &[u8]) -> EvalResult<'tcx, Pointer> {
+        let ptr = self.allocate(s.len() + 1, 1)?;
+        self.write_bytes(ptr, s)?;
+        self.write_bytes(ptr.offset(s.len() as isize), &[0])?;
+        Ok(ptr)
+    }
+
     pub fn read_ptr(&self, ptr: Pointer) -> EvalResult<'tcx, Pointer> {
         let size = self.pointer_size();
         self.check_defined(ptr, size)?;
@@ -520,10 +984,15 @@ impl<'a, 'tcx> Memory<'a, 'tcx> {
         let bytes = self.get_bytes_unchecked(ptr, size)?;
         let offset = read_target_uint(endianess, bytes).unwrap() as usize;
         let alloc = self.get(ptr.alloc_id)?;
-        match alloc.relocations.get(&ptr.offset) {
-            Some(&alloc_id) => Ok(Pointer::new(alloc_id, offset)),
-            None => Ok(Pointer::from_int(offset)),
+        if let Some(&alloc_id) = alloc.relocations.get(&ptr.offset) {
+            return Ok(Pointer::new(alloc_id, offset));
         }
+        // Plain bytes are an integer pointer, but the bytes of a pointer that starts elsewhere
+        // are not.
+        if let Some((&pointer_start, _)) = self.relocations(ptr, size)?.next() {
+            return Err(EvalError::PartialPointerRead { ptr: ptr, pointer_start: pointer_start });
+        }
+        Ok(Pointer::from_int(offset))
     }
 
     pub fn write_ptr(&mut self, dest: Pointer, ptr: Pointer) -> EvalResult<'tcx, ()> {
@@ -549,6 +1018,31 @@ impl<'a, 'tcx> Memory<'a, 'tcx> {
         self.write_uint(dest, bits, size)
     }
 
+    /// Reads a `PrimVal` of the given kind, the inverse of `write_primval`. Pointers are only
+    /// recognized for the `Ptr` and `FnPtr` kinds; reading an integer kind over a pointer is a
+    /// `ReadPointerAsBytes` error.
+    pub fn read_primval(&self, ptr: Pointer, kind: PrimValKind) -> EvalResult<'tcx, PrimVal> {
+        use primval::PrimValKind::*;
+        let bits = match kind {
+            I8  => self.read_int(ptr, 1)? as u64,
+            I16 => self.read_int(ptr, 2)? as u64,
+            I32 => self.read_int(ptr, 4)? as u64,
+            I64 => self.read_int(ptr, 8)? as u64,
+            U8  => self.read_uint(ptr, 1)?,
+            U16 => self.read_uint(ptr, 2)?,
+            U32 | Char => self.read_uint(ptr, 4)?,
+            U64 => self.read_uint(ptr, 8)?,
+            F32 => return Ok(PrimVal::from_f32(self.read_f32(ptr)?)),
+            F64 => return Ok(PrimVal::from_f64(self.read_f64(ptr)?)),
+            Bool => return Ok(PrimVal::from_bool(self.read_bool(ptr)?)),
+            Ptr | FnPtr => {
+                let p = self.read_ptr(ptr)?;
+                return Ok(PrimVal::new_with_relocation(p.offset as u64, kind, p.alloc_id));
+            }
+        };
+        Ok(PrimVal::new(bits, kind))
+    }
+
     pub fn read_bool(&self, ptr: Pointer) -> EvalResult<'tcx, bool> {
         let bytes = self.get_bytes(ptr, 1, self.layout.i1_align.abi() as usize)?;
         match bytes[0] {
@@ -655,6 +1149,17 @@ impl<'a, 'tcx> Memory<'a, 'tcx> {
         Ok(self.get(ptr.alloc_id)?.relocations.range(Included(&start), Excluded(&end)))
     }
 
+    /// Returns the relocations whose keys lie within `size` bytes from `ptr`, as pairs of their
+    /// offset relative to `ptr` and the allocation they point to. A relocation starting before
+    /// `ptr` is not included, even if it overlaps the range.
+    pub fn relocations_in_range(&self, ptr: Pointer, size: usize) -> EvalResult<'tcx, Vec<(usize, AllocId)>> {
+        let start = ptr.offset;
+        let end = ptr.offset + size;
+        Ok(self.get(ptr.alloc_id)?.relocations.range(Included(&start), Excluded(&end))
+            .map(|(&offset, &alloc_id)| (offset - start, alloc_id))
+            .collect())
+    }
+
     fn clear_relocations(&mut self, ptr: Pointer, size: usize) -> EvalResult<'tcx, ()> {
         // Find all relocations overlapping the given range.
         let keys: Vec<_> = self.relocations(ptr, size)?.map(|(&k, _)| k).collect();
@@ -666,12 +1171,13 @@ impl<'a, 'tcx> Memory<'a, 'tcx> {
         let first = *keys.first().unwrap();
         let last = *keys.last().unwrap() + self.pointer_size();
 
+        let zero = !self.check_uninit;
         let alloc = self.get_mut(ptr.alloc_id)?;
 
         // Mark parts of the outermost relocations as undefined if they partially fall outside the
         // given range.
-        if first < start { alloc.undef_mask.set_range(first, start, false); }
-        if last > end { alloc.undef_mask.set_range(end, last, false); }
+        if first < start { alloc.mark_undef(first, start, zero); }
+        if last > end { alloc.mark_undef(end, last, zero); }
 
         // Forget all the relocations.
         for k in keys { alloc.relocations.remove(&k); }
@@ -683,18 +1189,16 @@ impl<'a, 'tcx> Memory<'a, 'tcx> {
         let overlapping_start = self.relocations(ptr, 0)?.count();
         let overlapping_end = self.relocations(ptr.offset(size as isize), 0)?.count();
         if overlapping_start + overlapping_end != 0 {
-            return Err(EvalError::ReadPointerAsBytes);
+            return Err(EvalError::ReadPointerAsBytes { range: Some((ptr, size)) });
         }
         Ok(())
     }
 
-    fn copy_relocations(&mut self, src: Pointer, dest: Pointer, size: usize) -> EvalResult<'tcx, ()> {
-        let relocations: Vec<_> = self.relocations(src, size)?
-            .map(|(&offset, &alloc_id)| {
-                // Update relocation offsets for the new positions in the destination allocation.
-                (offset + dest.offset - src.offset, alloc_id)
-            })
-            .collect();
+    /// Inserts the `(offset, alloc_id)` relocations saved from the source of a copy at their new
+    /// positions relative to `dest`. `copy` has already checked that no relocation overlaps the
+    /// edges of the range.
+    fn copy_relocations(&mut self, dest: Pointer, relocations: Vec<(usize, AllocId)>) -> EvalResult<'tcx, ()> {
+        let relocations = relocations.into_iter().map(|(offset, alloc_id)| (offset + dest.offset, alloc_id));
         self.get_mut(dest.alloc_id)?.relocations.extend(relocations);
         Ok(())
     }
@@ -703,35 +1207,66 @@ impl<'a, 'tcx> Memory<'a, 'tcx> {
 /// Undefined bytes
 impl<'a, 'tcx> Memory<'a, 'tcx> {
     // FIXME(solson): This is a very naive, slow version.
-    fn copy_undef_mask(&mut self, src: Pointer, dest: Pointer, size: usize) -> EvalResult<'tcx, ()> {
-        // The bits have to be saved locally before writing to dest in case src and dest overlap.
-        let mut v = Vec::with_capacity(size);
-        for i in 0..size {
-            let defined = self.get(src.alloc_id)?.undef_mask.get(src.offset + i);
-            v.push(defined);
-        }
-        for (i, defined) in v.into_iter().enumerate() {
-            self.get_mut(dest.alloc_id)?.undef_mask.set(dest.offset + i, defined);
+    fn definedness(&self, ptr: Pointer, size: usize) -> EvalResult<'tcx, Vec<bool>> {
+        let alloc = self.get(ptr.alloc_id)?;
+        Ok((0..size).map(|i| alloc.undef_mask.get(ptr.offset + i)).collect())
+    }
+
+    /// Applies the definedness saved from the source of a copy to the bytes starting at `dest`.
+    fn copy_undef_mask(&mut self, dest: Pointer, definedness: Vec<bool>) -> EvalResult<'tcx, ()> {
+        let alloc = self.get_mut(dest.alloc_id)?;
+        for (i, defined) in definedness.into_iter().enumerate() {
+            alloc.undef_mask.set(dest.offset + i, defined);
         }
         Ok(())
     }
 
     fn check_defined(&self, ptr: Pointer, size: usize) -> EvalResult<'tcx, ()> {
         let alloc = self.get(ptr.alloc_id)?;
-        if !alloc.undef_mask.is_range_defined(ptr.offset, ptr.offset + size) {
+        if self.check_uninit && !alloc.undef_mask.is_range_defined(ptr.offset, ptr.offset + size) {
             return Err(EvalError::ReadUndefBytes);
         }
         Ok(())
     }
 
+    /// Marks all bytes of `ptr..ptr + size` that aren't covered by one of the given
+    /// `(offset, size)` field ranges as undefined. Such padding bytes can still be copied along
+    /// with the rest of the value, but reading them as a value is an error.
+    pub fn mark_padding_undef(&mut self, ptr: Pointer, size: usize, fields: &[(usize, usize)])
+        -> EvalResult<'tcx, ()>
+    {
+        if size == 0 {
+            return Ok(())
+        }
+        let mut covered = vec![false; size];
+        for &(offset, field_size) in fields {
+            for byte in &mut covered[offset..offset + field_size] {
+                *byte = true;
+            }
+        }
+        let zero = !self.check_uninit;
+        let alloc = self.get_mut(ptr.alloc_id)?;
+        for (i, covered) in covered.into_iter().enumerate() {
+            if !covered {
+                alloc.mark_undef(ptr.offset + i, ptr.offset + i + 1, zero);
+            }
+        }
+        Ok(())
+    }
+
     pub fn mark_definedness(&mut self, ptr: Pointer, size: usize, new_state: bool)
         -> EvalResult<'tcx, ()>
     {
         if size == 0 {
             return Ok(())
         }
+        let zero = !self.check_uninit;
         let mut alloc = self.get_mut(ptr.alloc_id)?;
-        alloc.undef_mask.set_range(ptr.offset, ptr.offset + size, new_state);
+        if new_state {
+            alloc.undef_mask.set_range(ptr.offset, ptr.offset + size, true);
+        } else {
+            alloc.mark_undef(ptr.offset, ptr.offset + size, zero);
+        }
         Ok(())
     }
 }
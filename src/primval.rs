@@ -184,7 +184,7 @@ impl PrimVal {
 
     pub fn expect_f64(self, error_msg: &str) -> f64 {
         match self.kind {
-            PrimValKind::F32 => bits_to_f64(self.bits),
+            PrimValKind::F64 => bits_to_f64(self.bits),
             _ => bug!("{}", error_msg),
         }
     }
@@ -364,6 +364,11 @@ pub fn binary_op<'tcx>(
         (Add, k) if k.is_int() => return int_arithmetic!(k, overflowing_add, l, r),
         (Sub, k) if k.is_int() => return int_arithmetic!(k, overflowing_sub, l, r),
         (Mul, k) if k.is_int() => return int_arithmetic!(k, overflowing_mul, l, r),
+        // The `Assert`s in front of divisions normally catch this, but intrinsics like
+        // `unchecked_div` come without them and the host would panic.
+        (Div, k) |
+        (Rem, k) if k.is_int() && r == 0 => return Err(EvalError::DivisionByZero),
+
         (Div, k) if k.is_int() => return int_arithmetic!(k, overflowing_div, l, r),
         (Rem, k) if k.is_int() => return int_arithmetic!(k, overflowing_rem, l, r),
 
@@ -0,0 +1,10 @@
+fn main() {
+    let mut x = [0u32; 4];
+    let x_ptr = &mut x as *mut [u32; 4] as *mut u8;
+    unsafe {
+        // the allocation itself is aligned for `u32`, but offset 2 into it is not
+        let y_ptr = x_ptr.offset(2) as *mut u32;
+        *y_ptr = 42; //~ ERROR tried to write memory with alignment 2, but alignment 4 is required
+    }
+    panic!("unreachable in miri");
+}
@@ -0,0 +1,9 @@
+fn main() {
+    // miri always gives allocations the worst possible alignment, so a `u8` array is guaranteed
+    // to be at the virtual location 1 (so one byte offset from the ultimate alignemnt location 0)
+    let x = [0u8; 20];
+    let x_ptr: *const u8 = &x[0];
+    let y_ptr = x_ptr as *const u64;
+    let y = unsafe { *y_ptr } + 1; //~ ERROR tried to read memory with alignment 1, but alignment
+    panic!("unreachable in miri: {}", y);
+}
@@ -0,0 +1,7 @@
+#![feature(custom_attribute, attr_literals, box_syntax)]
+#![miri(max_alloc_size=1000)]
+
+fn main() {
+    let _x = box [0u8; 2000];
+    //~^ERROR tried to allocate 2000 bytes, but a single allocation may be at most 1000 bytes
+}
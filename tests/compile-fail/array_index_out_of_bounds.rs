@@ -0,0 +1,8 @@
+fn index(i: usize) -> u8 {
+    let array = [1u8, 2, 3, 4];
+    array[i] //~ ERROR: index out of bounds: the len is 4 but the index is 4
+}
+
+fn main() {
+    index(4);
+}
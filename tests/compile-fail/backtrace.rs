@@ -0,0 +1,15 @@
+fn divide(a: u32, b: u32) -> u32 {
+    a / b //~ ERROR attempted to divide by zero
+}
+
+fn middle(a: u32, b: u32) -> u32 {
+    divide(a, b) //~ NOTE inside call to divide
+}
+
+fn outer(a: u32) -> u32 {
+    middle(a, 0) //~ NOTE inside call to middle
+}
+
+fn main() { //~ NOTE inside call to main
+    outer(1); //~ NOTE inside call to outer
+}
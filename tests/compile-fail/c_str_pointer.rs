@@ -0,0 +1,10 @@
+extern {
+    fn strlen(s: *const u8) -> usize;
+}
+
+fn main() {
+    let x = 0u8;
+    let p = &x as *const u8;
+    let n = unsafe { strlen(&p as *const *const u8 as *const u8) }; //~ ERROR: a raw memory access tried to access part of a pointer value as raw bytes
+    panic!("this should never print: {}", n);
+}
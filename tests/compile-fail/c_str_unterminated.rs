@@ -0,0 +1,9 @@
+extern {
+    fn strlen(s: *const u8) -> usize;
+}
+
+fn main() {
+    let buf = [b'a'; 4];
+    let n = unsafe { strlen(buf.as_ptr()) }; //~ ERROR: which has size 4
+    panic!("this should never print: {}", n);
+}
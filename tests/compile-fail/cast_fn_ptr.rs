@@ -5,5 +5,5 @@ fn main() {
         std::mem::transmute::<fn(), fn(i32)>(f)
     };
 
-    g(42) //~ ERROR tried to call a function of type
+    g(42) //~ ERROR tried to call a function taking 0 arguments with 1 arguments
 }
@@ -0,0 +1,9 @@
+fn main() {
+    fn f(_: i32) {}
+
+    let g = unsafe {
+        std::mem::transmute::<fn(i32), fn()>(f)
+    };
+
+    g() //~ ERROR tried to call a function taking 1 arguments with 0 arguments
+}
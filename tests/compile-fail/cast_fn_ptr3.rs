@@ -0,0 +1,9 @@
+fn main() {
+    fn f(_: i32) {}
+
+    let g = unsafe {
+        std::mem::transmute::<fn(i32), fn(u32)>(f)
+    };
+
+    g(42) //~ ERROR tried to call a function of type
+}
@@ -0,0 +1,14 @@
+use std::mem::size_of;
+use std::ptr;
+
+fn main() {
+    let x = 5;
+    let mut dest: &i32 = &x;
+    let src = [0u8; 16];
+    unsafe {
+        // plain bytes copied over a pointer must not keep the old pointer's allocation around
+        ptr::copy_nonoverlapping(src.as_ptr(), &mut dest as *mut &i32 as *mut u8, size_of::<&i32>());
+    }
+    let y = *dest; //~ ERROR: tried to access memory through an invalid pointer
+    panic!("this should never print: {}", y);
+}
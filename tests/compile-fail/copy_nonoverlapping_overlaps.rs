@@ -0,0 +1,10 @@
+use std::ptr;
+
+fn main() {
+    let mut a = [1u8, 2, 3, 4];
+    unsafe {
+        let p = a.as_mut_ptr();
+        ptr::copy_nonoverlapping(p, p.offset(1), 2); //~ ERROR: copy_nonoverlapping called on overlapping ranges
+    }
+    panic!("this should never print: {:?}", a);
+}
@@ -0,0 +1,12 @@
+#![feature(alloc, heap_api)]
+
+extern crate alloc;
+
+use alloc::heap::{allocate, deallocate};
+
+fn main() {
+    unsafe {
+        let p = allocate(4, 4);
+        deallocate(p.offset(1), 3, 4); //~ ERROR: tried to deallocate with a pointer not to the beginning of an existing object
+    }
+}
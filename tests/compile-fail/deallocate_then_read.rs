@@ -0,0 +1,15 @@
+#![feature(alloc, heap_api)]
+
+extern crate alloc;
+
+use alloc::heap::{allocate, deallocate};
+
+fn main() {
+    unsafe {
+        let p = allocate(4, 4);
+        *p = 5;
+        deallocate(p, 4, 4);
+        let x = *p; //~ ERROR: dangling pointer was dereferenced
+        panic!("this should never print: {}", x);
+    }
+}
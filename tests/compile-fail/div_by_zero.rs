@@ -0,0 +1,7 @@
+fn div(a: i32, b: i32) -> i32 {
+    a / b //~ ERROR: attempted to divide by zero
+}
+
+fn main() {
+    div(1, 0);
+}
@@ -0,0 +1,7 @@
+fn div(a: i32, b: i32) -> i32 {
+    a / b //~ ERROR: attempted a division or remainder that overflows
+}
+
+fn main() {
+    div(std::i32::MIN, -1);
+}
@@ -0,0 +1,13 @@
+#![feature(alloc, heap_api)]
+
+extern crate alloc;
+
+use alloc::heap::{allocate, deallocate};
+
+fn main() {
+    unsafe {
+        let p = allocate(4, 4);
+        deallocate(p, 4, 4);
+        deallocate(p, 4, 4); //~ ERROR: which was already deallocated
+    }
+}
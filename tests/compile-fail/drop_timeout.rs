@@ -0,0 +1,17 @@
+//error-pattern: reached the configured maximum execution time
+#![feature(custom_attribute, attr_literals)]
+#![miri(step_limit=1000)]
+
+// The destructor runs through the same step limit as the rest of the program.
+
+struct Forever;
+
+impl Drop for Forever {
+    fn drop(&mut self) {
+        loop {}
+    }
+}
+
+fn main() {
+    let _forever = Forever;
+}
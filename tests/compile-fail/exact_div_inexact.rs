@@ -0,0 +1,6 @@
+#![feature(core_intrinsics)]
+
+fn main() {
+    let x = unsafe { std::intrinsics::exact_div(7u8, 2) }; //~ ERROR: called `exact_div` on operands that don't divide evenly
+    panic!("this should never print: {}", x);
+}
@@ -0,0 +1,9 @@
+#![allow(unconditional_recursion)]
+
+fn recurse(n: u64) -> u64 {
+    recurse(n + 1) //~ ERROR reached the configured maximum number of stack frames
+}
+
+fn main() {
+    recurse(0);
+}
@@ -0,0 +1,12 @@
+#![feature(custom_attribute, attr_literals)]
+#![miri(deterministic_addresses=true)]
+
+fn main() {
+    let b = Box::new(42u32);
+    let addr = &*b as *const u32 as usize;
+    drop(b);
+    // The address belongs to no live allocation anymore, so this is just an integer pointer.
+    let p = addr as *const u32;
+    let x = unsafe { *p }; //~ ERROR: tried to access memory through an invalid pointer
+    panic!("this should never print: {}", x);
+}
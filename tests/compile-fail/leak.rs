@@ -0,0 +1,9 @@
+#![feature(custom_attribute, attr_literals)]
+#![miri(check_leaks=true)]
+
+fn main() { //~ ERROR: the program leaked 1 heap allocation(s)
+    let freed = Box::new(1u32);
+    let leaked = Box::new(2u32);
+    assert_eq!(*freed + *leaked, 3);
+    std::mem::forget(leaked);
+}
@@ -0,0 +1,12 @@
+use std::{mem, ptr};
+
+fn main() {
+    let mut x = [0u8; 4];
+    let y = 0x0102030405060708u64;
+    unsafe {
+        // the eight bytes of a `u64` starting one byte before the end of a four byte allocation
+        let dest = x.as_mut_ptr().offset(3);
+        ptr::copy_nonoverlapping(&y as *const u64 as *const u8, dest, mem::size_of::<u64>()); //~ ERROR: which has size 4
+    }
+    panic!("this should never print: {:?}", x);
+}
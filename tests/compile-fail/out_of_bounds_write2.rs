@@ -0,0 +1,8 @@
+fn main() {
+    let mut x = Box::new(1u32);
+    let p = &mut *x as *mut u32;
+    unsafe {
+        *p.offset(1) = 2; //~ ERROR: which has size 4
+    }
+    panic!("this should never print");
+}
@@ -0,0 +1,15 @@
+fn main() {
+    let x = 42;
+    let mut p = &x;
+    let slot = &mut p as *mut &i32 as *mut usize;
+    unsafe {
+        // Writing an integer over the whole pointer leaves plain data behind.
+        *slot = 8;
+        assert_eq!(*slot, 8);
+        let bytes = slot as *const u8;
+        let sum: usize = (0..std::mem::size_of::<usize>()).map(|i| *bytes.offset(i as isize) as usize).sum();
+        assert_eq!(sum, 8);
+    }
+    let y = *p; //~ ERROR: tried to access memory through an invalid pointer
+    panic!("this should never print: {}", y);
+}
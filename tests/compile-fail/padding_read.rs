@@ -0,0 +1,17 @@
+#[repr(C)]
+struct Padded {
+    a: u8,
+    b: u32,
+}
+
+fn main() {
+    let p = Padded { a: 1, b: 2 };
+    // copying the whole struct, padding included, is fine
+    let q = p;
+    assert_eq!(q.a, 1);
+    assert_eq!(q.b, 2);
+    let bytes = &q as *const Padded as *const u8;
+    let pad = unsafe { *bytes.offset(1) };
+    let x = pad + 1; //~ ERROR attempted to read undefined bytes
+    panic!("this should never print: {}", x);
+}
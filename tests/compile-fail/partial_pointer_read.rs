@@ -0,0 +1,15 @@
+use std::{mem, ptr};
+
+fn main() {
+    let x = 5u8;
+    let p: *const u8 = &x;
+    let size = mem::size_of::<*const u8>();
+    let mut buf = [0u64; 4];
+    let bytes = buf.as_mut_ptr() as *mut u8;
+    unsafe {
+        // Moves the bytes of `p` to the middle of `buf`, so they straddle the pointer read below.
+        ptr::copy_nonoverlapping(&p as *const *const u8 as *const u8, bytes.offset(size as isize / 2), size);
+        let q = *(bytes.offset(size as isize) as *const *const u8); //~ ERROR but there is a pointer at byte
+        panic!("this should never print: {:?}", q);
+    }
+}
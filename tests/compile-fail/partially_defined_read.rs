@@ -0,0 +1,8 @@
+use std::mem;
+
+fn main() {
+    let mut slot: u64 = unsafe { mem::uninitialized() };
+    unsafe { *(&mut slot as *mut u64 as *mut u8) = 42; }
+    let x = slot; //~ ERROR: attempted to read undefined bytes
+    panic!("this should never print: {}", x);
+}
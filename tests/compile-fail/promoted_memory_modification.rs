@@ -0,0 +1,8 @@
+fn main() {
+    // `&[1, 2, 3]` is promoted to a constant, so it lives in frozen memory.
+    let a: &'static [i32; 3] = &[1, 2, 3];
+    assert_eq!(a[1], 2);
+    let p = a as *const [i32; 3] as *mut i32;
+    unsafe { *p = 5; } //~ ERROR: tried to modify constant memory
+    panic!("this should never print: {:?}", a);
+}
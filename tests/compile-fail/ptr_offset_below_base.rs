@@ -0,0 +1,9 @@
+fn main() {
+    let x = [1u32, 2, 3];
+    let p = &x[1] as *const u32;
+    // one element back is still in bounds, two elements back is before the allocation
+    let y = unsafe { *p.offset(-1) };
+    assert_eq!(y, 1);
+    let q = unsafe { p.offset(-2) }; //~ ERROR: pointer arithmetic overflowed
+    panic!("this should never print: {:?}", q);
+}
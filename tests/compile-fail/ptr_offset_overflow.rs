@@ -0,0 +1,5 @@
+fn main() {
+    let p = (usize::max_value() - 1) as *const u16;
+    let q = unsafe { p.offset(1) }; //~ ERROR: pointer arithmetic overflowed
+    panic!("this should never print: {:?}", q);
+}
@@ -0,0 +1,11 @@
+#![feature(core_intrinsics)]
+
+use std::intrinsics::raw_eq;
+use std::mem;
+
+fn main() {
+    let a: [u8; 4] = unsafe { mem::uninitialized() };
+    let b = [0u8; 4];
+    let eq = unsafe { raw_eq(&a, &b) }; //~ ERROR: attempted to read undefined bytes
+    panic!("this should never print: {}", eq);
+}
@@ -0,0 +1,7 @@
+fn main() {
+    let x = 0u8;
+    let r = &x;
+    let p = &r as *const &u8 as *const u8;
+    let b = unsafe { *p.offset(2) }; //~ ERROR: as raw bytes: bytes 2..3 of allocation
+    panic!("this should never print: {}", b);
+}
@@ -0,0 +1,7 @@
+fn rem(a: i32, b: i32) -> i32 {
+    a % b //~ ERROR: attempted to divide by zero or to calculate the remainder with a divisor of zero
+}
+
+fn main() {
+    rem(1, 0);
+}
@@ -0,0 +1,16 @@
+#![feature(repr_simd, platform_intrinsics)]
+#![allow(non_camel_case_types)]
+
+#[repr(simd)]
+#[derive(Copy, Clone)]
+struct u32x4(u32, u32, u32, u32);
+
+extern "platform-intrinsic" {
+    fn simd_extract<T, E>(x: T, idx: u32) -> E;
+}
+
+fn main() {
+    let v = u32x4(1, 2, 3, 4);
+    let lane: u32 = unsafe { simd_extract(v, 4) }; //~ ERROR index out of bounds: the len is 4 but the index is 4
+    panic!("this should never print: {}", lane);
+}
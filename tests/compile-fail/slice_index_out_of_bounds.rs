@@ -0,0 +1,8 @@
+fn get(a: &[u16], i: usize) -> u16 {
+    a[i] //~ ERROR: index out of bounds: the len is 2 but the index is 5
+}
+
+fn main() {
+    let array = [10u16, 20, 30, 40];
+    get(&array[1..3], 5);
+}
@@ -0,0 +1,11 @@
+#![feature(custom_attribute, attr_literals)]
+#![miri(strict_provenance=true)]
+
+// Strict provenance hands out addresses on its own, so only the access fails, not the cast.
+fn main() {
+    let x = [1u32, 2, 3];
+    let addr = &x[1] as *const u32 as usize;
+    let p = addr as *const u32;
+    let y = unsafe { *p } + 1; //~ ERROR: tried to access memory through a pointer cast from an integer under strict provenance
+    panic!("this should never print: {}", y);
+}
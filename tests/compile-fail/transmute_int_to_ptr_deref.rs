@@ -0,0 +1,13 @@
+#![feature(custom_attribute, attr_literals)]
+#![miri(deterministic_addresses=true)]
+
+use std::mem::transmute;
+
+fn main() {
+    let x = 42;
+    let addr = unsafe { transmute::<&i32, usize>(&x) };
+    // the integer lost the pointer's provenance, so it can't be used to access `x`
+    let p = unsafe { transmute::<usize, *const i32>(addr) };
+    let y = unsafe { *p } + 1; //~ ERROR: tried to access memory through an invalid pointer
+    panic!("this should never print: {}", y);
+}
@@ -0,0 +1,8 @@
+use std::mem::transmute;
+
+fn main() {
+    let x = 42;
+    // without deterministic addresses, pointers have no integer value to transmute into
+    let addr = unsafe { transmute::<&i32, usize>(&x) }; //~ ERROR: a raw memory access tried to access part of a pointer value as raw bytes
+    panic!("this should never print: {}", addr);
+}
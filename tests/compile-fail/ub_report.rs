@@ -0,0 +1,6 @@
+fn main() {
+    let v = [1u8, 2];
+    let p = &v as *const [u8; 2] as *const u8;
+    let x = unsafe { *p.offset(5) }; //~ ERROR: undefined behavior: memory access of 5..6 outside bounds of allocation
+    panic!("this should never print: {}", x);
+}
@@ -0,0 +1,6 @@
+#![feature(core_intrinsics)]
+
+fn main() {
+    let x = unsafe { std::intrinsics::unchecked_div(1u8, 0) }; //~ ERROR: called an unchecked division or remainder intrinsic with a divisor of zero
+    panic!("this should never print: {}", x);
+}
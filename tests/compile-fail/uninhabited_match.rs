@@ -0,0 +1,6 @@
+enum Void {}
+
+fn main() {
+    let v: Void = unsafe { std::mem::transmute(()) };
+    match v {} //~ ERROR: entered unreachable code
+}
@@ -0,0 +1,5 @@
+#![feature(core_intrinsics)]
+
+fn main() {
+    unsafe { std::intrinsics::unreachable() } //~ ERROR: entered unreachable code
+}
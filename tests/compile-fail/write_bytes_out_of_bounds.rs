@@ -0,0 +1,7 @@
+use std::ptr;
+
+fn main() {
+    let mut buf = [0u16; 3];
+    unsafe { ptr::write_bytes(buf.as_mut_ptr().offset(1), 0xff, 3); } //~ ERROR: memory access of 2..8 outside bounds of allocation
+    panic!("this should never print: {:?}", buf);
+}
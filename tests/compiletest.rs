@@ -27,6 +27,20 @@ fn run_pass() {
     compiletest::run_tests(&config);
 }
 
+/// Asks rustc how many bits a pointer has on `target`.
+fn target_pointer_width(sysroot: &str, target: &str) -> String {
+    let output = std::process::Command::new("rustc")
+        .args(&["--print", "cfg", "--sysroot", sysroot, "--target", target])
+        .output()
+        .expect("failed to run rustc --print cfg");
+    let cfg = String::from_utf8(output.stdout).unwrap();
+    cfg.lines()
+        .filter_map(|line| line.splitn(2, "target_pointer_width=").nth(1))
+        .map(|width| width.trim_matches('"').to_owned())
+        .next()
+        .expect("rustc didn't print target_pointer_width")
+}
+
 fn for_all_targets<F: FnMut(String)>(sysroot: &str, mut f: F) {
     for target in std::fs::read_dir(format!("{}/lib/rustlib/", sysroot)).unwrap() {
         let target = target.unwrap();
@@ -56,6 +70,7 @@ fn compile_test() {
     };
     run_pass();
     for_all_targets(&sysroot, |target| {
+        let pointer_width = target_pointer_width(&sysroot, &target);
         let files = std::fs::read_dir("tests/run-pass").unwrap();
         let files: Box<Iterator<Item=_>> = if let Ok(path) = std::env::var("MIRI_RUSTC_TEST") {
             Box::new(files.chain(std::fs::read_dir(path).unwrap()))
@@ -84,10 +99,32 @@ fn compile_test() {
             let paths = std::env::join_paths(&[libs, sysroot]).unwrap();
             cmd.env(compiletest::procsrv::dylib_env_var(), paths);
 
+            // A `.stdout` file next to the test holds the output the program must print. If the
+            // output depends on the size of pointers, there is a `.32bit.stdout` and a
+            // `.64bit.stdout` file instead.
+            let width_stdout = path.with_extension(format!("{}bit.stdout", pointer_width));
+            let stdout_path = if width_stdout.exists() { width_stdout } else { path.with_extension("stdout") };
+            let expected_stdout = std::fs::File::open(stdout_path).ok().map(|mut file| {
+                let mut s = String::new();
+                std::io::Read::read_to_string(&mut file, &mut s).unwrap();
+                s
+            });
+
             match cmd.output() {
                 Ok(ref output) if output.status.success() => {
-                    success += 1;
-                    writeln!(stderr.lock(), "ok").unwrap()
+                    let stdout = std::str::from_utf8(&output.stdout).unwrap();
+                    match expected_stdout {
+                        Some(ref expected) if expected != stdout => {
+                            failed += 1;
+                            writeln!(stderr.lock(), "FAILED with unexpected output").unwrap();
+                            writeln!(stderr.lock(), "expected: \n {}", expected).unwrap();
+                            writeln!(stderr.lock(), "stdout: \n {}", stdout).unwrap();
+                        }
+                        _ => {
+                            success += 1;
+                            writeln!(stderr.lock(), "ok").unwrap()
+                        }
+                    }
                 },
                 Ok(output) => {
                     let output_err = std::str::from_utf8(&output.stderr).unwrap();
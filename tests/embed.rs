@@ -0,0 +1,418 @@
+//! Tests of the library API, on programs in `tests/embed` compiled in-process.
+
+#![feature(rustc_private)]
+
+extern crate miri;
+extern crate rustc;
+
+mod helpers;
+
+use helpers::find_fn;
+use miri::{EvalConfig, EvalContext, EvalError, PrimVal, PrimValKind, Value};
+use rustc::hir::def_id::DefId;
+use rustc::mir::mir_map::MirMap;
+use rustc::ty::subst::Substs;
+use rustc::ty::TyCtxt;
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+const MEMORY_SIZE: usize = 1024 * 1024;
+const STACK_LIMIT: usize = 100;
+const STEP_LIMIT: u64 = 1000_000;
+
+#[test]
+fn const_fn_call() {
+    fn check<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir_map: &'a MirMap<'tcx>, _: DefId) {
+        let mut ecx = EvalContext::new(tcx, mir_map, MEMORY_SIZE, STACK_LIMIT);
+        let substs = Substs::empty(tcx);
+
+        let arg = Value::ByVal(PrimVal::new(21, PrimValKind::U64));
+        match ecx.eval_const_fn_call(find_fn(tcx, mir_map, "double"), substs, &[arg], STEP_LIMIT) {
+            Ok(Value::ByRef(ptr)) => assert_eq!(ecx.memory().read_usize(ptr).unwrap(), 42),
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        // Reading an immutable static is fine.
+        match ecx.eval_const_fn_call(find_fn(tcx, mir_map, "read_limit"), substs, &[], STEP_LIMIT) {
+            Ok(Value::ByRef(ptr)) => assert_eq!(ecx.memory().read_usize(ptr).unwrap(), 5),
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        // `double` takes exactly one argument.
+        for args in &[&[][..], &[arg, arg][..]] {
+            match ecx.eval_const_fn_call(find_fn(tcx, mir_map, "double"), substs, args, STEP_LIMIT) {
+                Err(EvalError::ArgumentCountMismatch { expected: 1, got }) if got == args.len() => {}
+                result => panic!("unexpected result {:?}", result),
+            }
+            assert!(ecx.stack().is_empty());
+        }
+
+        for name in &["boxed", "bump"] {
+            match ecx.eval_const_fn_call(find_fn(tcx, mir_map, name), substs, &[], STEP_LIMIT) {
+                Err(EvalError::NotConst(_)) => {}
+                result => panic!("unexpected result of `{}`: {:?}", name, result),
+            }
+            assert!(ecx.stack().is_empty(), "`{}` left frames behind", name);
+        }
+    }
+    helpers::run("const_eval", check);
+}
+
+#[test]
+fn const_by_path() {
+    fn read<'a, 'tcx>(ecx: &EvalContext<'a, 'tcx>, value: Value, size: usize) -> u64 {
+        match value {
+            Value::ByVal(primval) => primval.bits,
+            Value::ByRef(ptr) => ecx.memory().read_uint(ptr, size).unwrap(),
+            Value::ByValPair(..) => panic!("unexpected pair {:?}", value),
+        }
+    }
+
+    fn check<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir_map: &'a MirMap<'tcx>, _: DefId) {
+        let mut ecx = EvalContext::new(tcx, mir_map, MEMORY_SIZE, STACK_LIMIT);
+
+        let x = ecx.eval_const_by_path("X", STEP_LIMIT).unwrap();
+        assert_eq!(read(&ecx, x, 4), 42);
+        let y = ecx.eval_const_by_path("inner::Y", STEP_LIMIT).unwrap();
+        assert_eq!(read(&ecx, y, 8), 43);
+
+        match ecx.eval_const_by_path("Z", STEP_LIMIT) {
+            Err(EvalError::NoSuchConstant(ref path)) if path == "Z" => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+        // `main` exists, but isn't a constant.
+        match ecx.eval_const_by_path("main", STEP_LIMIT) {
+            Err(EvalError::NoSuchConstant(_)) => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+    helpers::run("consts", check);
+}
+
+/// Runs `def_id`, which has to take no arguments, to completion.
+fn run_fn<'a, 'tcx>(ecx: &mut EvalContext<'a, 'tcx>, def_id: DefId) {
+    ecx.push_main_frame(def_id, &[]).unwrap();
+    while ecx.step().unwrap() {}
+}
+
+#[test]
+fn reset() {
+    fn check<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir_map: &'a MirMap<'tcx>, main: DefId) {
+        let mut ecx = EvalContext::new(tcx, mir_map, MEMORY_SIZE, STACK_LIMIT);
+        run_fn(&mut ecx, main);
+        assert_eq!(ecx.output(), "run 1\n");
+
+        // Without the reset, the second run would see the static its predecessor incremented.
+        ecx.reset();
+        assert_eq!(ecx.output(), "");
+        assert!(ecx.memory().leaks().is_empty());
+        run_fn(&mut ecx, main);
+        assert_eq!(ecx.output(), "run 1\n");
+    }
+    helpers::run("statics", check);
+}
+
+#[test]
+fn intercept() {
+    fn check<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir_map: &'a MirMap<'tcx>, main: DefId) {
+        let mut ecx = EvalContext::new(tcx, mir_map, MEMORY_SIZE, STACK_LIMIT);
+        let calls = Rc::new(Cell::new(0));
+        let helper_calls = calls.clone();
+        ecx.intercept("helper", move |args| {
+            helper_calls.set(helper_calls.get() + 1);
+            match args[0] {
+                Value::ByVal(arg) => assert_eq!(arg.bits, 1),
+                arg => panic!("unexpected argument {:?}", arg),
+            }
+            Value::ByVal(PrimVal::new(42, PrimValKind::U32))
+        });
+        // `main` asserts that it sees the canned value instead of what `helper` computes.
+        run_fn(&mut ecx, main);
+        assert_eq!(calls.get(), 1);
+
+        // Returning from a function that can't return is an error, not an endless loop.
+        ecx.intercept("stop", |_| Value::ByVal(PrimVal::from_bool(true)));
+        ecx.push_main_frame(find_fn(tcx, mir_map, "diverge"), &[]).unwrap();
+        let mut steps = 0;
+        loop {
+            match ecx.step() {
+                Ok(true) if steps < STEP_LIMIT => steps += 1,
+                Err(EvalError::Unreachable) => break,
+                result => panic!("unexpected result {:?}", result),
+            }
+        }
+    }
+    helpers::run("intercept", check);
+}
+
+#[test]
+fn output() {
+    fn check<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir_map: &'a MirMap<'tcx>, _: DefId) {
+        let mut ecx = EvalContext::new(tcx, mir_map, MEMORY_SIZE, STACK_LIMIT);
+        run_fn(&mut ecx, find_fn(tcx, mir_map, "first"));
+        assert_eq!(ecx.take_output(), "first\n");
+        run_fn(&mut ecx, find_fn(tcx, mir_map, "second"));
+        assert_eq!(ecx.take_output(), "second\n");
+        assert_eq!(ecx.output(), "");
+
+        // stderr isn't kept apart from stdout.
+        run_fn(&mut ecx, find_fn(tcx, mir_map, "both_streams"));
+        assert_eq!(ecx.take_output(), "out\nerr\nout again\n");
+    }
+    helpers::run("output", check);
+}
+
+#[test]
+fn grow_allocation() {
+    fn check<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir_map: &'a MirMap<'tcx>, _: DefId) {
+        let mut ecx = EvalContext::new(tcx, mir_map, MEMORY_SIZE, STACK_LIMIT);
+        let memory = ecx.memory_mut();
+        let ptr = memory.allocate(4, 4).unwrap();
+        memory.grow_allocation(ptr, 8).unwrap();
+        memory.write_uint(ptr.offset(4), 7, 4).unwrap();
+
+        match memory.grow_allocation(ptr, 2) {
+            Err(EvalError::InvalidGrowSize { allocation_size: 8, new_size: 2 }) => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+        match memory.grow_allocation(ptr.offset(4), 16) {
+            Err(EvalError::ReallocateNonBasePtr) => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+    helpers::run("empty", check);
+}
+
+#[test]
+fn memory_stats() {
+    fn check<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir_map: &'a MirMap<'tcx>, _: DefId) {
+        let mut ecx = EvalContext::new(tcx, mir_map, MEMORY_SIZE, STACK_LIMIT);
+        let memory = ecx.memory_mut();
+        let before = memory.stats();
+
+        let a = memory.allocate(4, 4).unwrap();
+        let b = memory.allocate(8, 8).unwrap();
+        let c = memory.allocate(16, 8).unwrap();
+        memory.write_ptr(b, a).unwrap();
+        memory.deallocate(c).unwrap();
+        let d = memory.allocate(2, 2).unwrap();
+        memory.deallocate(d).unwrap();
+
+        let after = memory.stats();
+        assert_eq!(after.live_allocations, before.live_allocations + 2);
+        assert_eq!(after.freed_allocations, before.freed_allocations + 2);
+        assert_eq!(after.live_bytes, before.live_bytes + 12);
+        assert_eq!(after.relocations, before.relocations + 1);
+        assert_eq!(after.next_id.0, before.next_id.0 + 4);
+    }
+    helpers::run("empty", check);
+}
+
+#[test]
+fn snapshot() {
+    fn check<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir_map: &'a MirMap<'tcx>, _: DefId) {
+        let mut ecx = EvalContext::new(tcx, mir_map, MEMORY_SIZE, STACK_LIMIT);
+        let ptr = ecx.memory_mut().allocate(4, 4).unwrap();
+        ecx.memory_mut().write_uint(ptr, 1, 4).unwrap();
+        let snapshot = ecx.snapshot();
+        ecx.memory_mut().write_uint(ptr, 2, 4).unwrap();
+        ecx.restore(snapshot.clone());
+        assert_eq!(ecx.memory().read_uint(ptr, 4).unwrap(), 1);
+
+        run_fn(&mut ecx, find_fn(tcx, mir_map, "bump"));
+        run_fn(&mut ecx, find_fn(tcx, mir_map, "greet"));
+        assert_eq!(ecx.take_output(), "1\nhello\n");
+
+        // The static and the string literal were first used after the snapshot, so they have to
+        // be evaluated again instead of pointing to allocations that are gone.
+        ecx.restore(snapshot);
+        run_fn(&mut ecx, find_fn(tcx, mir_map, "bump"));
+        run_fn(&mut ecx, find_fn(tcx, mir_map, "greet"));
+        assert_eq!(ecx.take_output(), "1\nhello\n");
+    }
+    helpers::run("snapshot", check);
+}
+
+#[test]
+fn ub_check_step_limit() {
+    fn check<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir_map: &'a MirMap<'tcx>, main: DefId) {
+        let mut ecx = EvalContext::new(tcx, mir_map, MEMORY_SIZE, STACK_LIMIT);
+        ecx.push_main_frame(main, &[]).unwrap();
+        match ecx.run_ub_check(1000) {
+            Err(EvalError::ExecutionTimeLimitReached) => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+    helpers::run("spin", check);
+}
+
+#[test]
+fn allocation_churn() {
+    fn check<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir_map: &'a MirMap<'tcx>, _: DefId) {
+        let mut ecx = EvalContext::new(tcx, mir_map, MEMORY_SIZE, STACK_LIMIT);
+        let memory = ecx.memory_mut();
+        let before = memory.stats();
+
+        // Each cycle frees the allocation of the previous one, and the last one stays live.
+        let mut ptr = memory.allocate(8, 8).unwrap();
+        for _ in 0..5 {
+            let next = memory.allocate(8, 8).unwrap();
+            memory.deallocate(ptr).unwrap();
+            ptr = next;
+        }
+
+        let after = memory.stats();
+        assert_eq!(after.live_allocations, before.live_allocations + 1);
+        assert_eq!(after.freed_allocations, before.freed_allocations + 5);
+        assert_eq!(after.live_bytes, before.live_bytes + 8);
+    }
+    helpers::run("empty", check);
+}
+
+#[test]
+fn checksum() {
+    fn check<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir_map: &'a MirMap<'tcx>, _: DefId) {
+        let mut first = EvalContext::new(tcx, mir_map, MEMORY_SIZE, STACK_LIMIT);
+        let mut second = EvalContext::new(tcx, mir_map, MEMORY_SIZE, STACK_LIMIT);
+        let ptr = first.memory_mut().allocate(4, 4).unwrap();
+        first.memory_mut().write_uint(ptr, 0x01020304, 4).unwrap();
+        let other_ptr = second.memory_mut().allocate(4, 4).unwrap();
+        second.memory_mut().write_uint(other_ptr, 0x01020304, 4).unwrap();
+        assert_eq!(first.memory().checksum(), second.memory().checksum());
+
+        first.memory_mut().write_uint(ptr.offset(3), 5, 1).unwrap();
+        assert!(first.memory().checksum() != second.memory().checksum());
+    }
+    helpers::run("empty", check);
+}
+
+#[test]
+fn deadline() {
+    fn check<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir_map: &'a MirMap<'tcx>, main: DefId) {
+        let mut ecx = EvalContext::new(tcx, mir_map, MEMORY_SIZE, STACK_LIMIT);
+        ecx.push_main_frame(main, &[]).unwrap();
+        match ecx.run_with_deadline(Instant::now() + Duration::from_millis(10)) {
+            Err(EvalError::WallClockDeadlineReached) => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+        // The loop is still running, so it can be continued.
+        assert!(!ecx.stack().is_empty());
+    }
+    helpers::run("spin", check);
+}
+
+#[test]
+fn leaked_since() {
+    fn check<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir_map: &'a MirMap<'tcx>, _: DefId) {
+        let mut ecx = EvalContext::new(tcx, mir_map, MEMORY_SIZE, STACK_LIMIT);
+        let watermark = ecx.alloc_watermark();
+        run_fn(&mut ecx, find_fn(tcx, mir_map, "balanced"));
+        assert!(ecx.leaked_since(watermark).is_empty());
+
+        let watermark = ecx.alloc_watermark();
+        run_fn(&mut ecx, find_fn(tcx, mir_map, "forget"));
+        let leaks = ecx.leaked_since(watermark);
+        assert_eq!(leaks.len(), 1);
+        assert!(leaks[0].0 >= watermark.0);
+    }
+    helpers::run("leaks", check);
+}
+
+#[test]
+fn step() {
+    fn bits(value: Option<Value>) -> Option<u64> {
+        match value {
+            Some(Value::ByVal(primval)) => Some(primval.bits),
+            None => None,
+            value => panic!("unexpected local {:?}", value),
+        }
+    }
+
+    fn check<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir_map: &'a MirMap<'tcx>, main: DefId) {
+        let mut ecx = EvalContext::new(tcx, mir_map, MEMORY_SIZE, STACK_LIMIT);
+        ecx.push_main_frame(main, &[]).unwrap();
+
+        // The locals of `main` are `x`, `y` and `_z`, in that order. Stop right after `y` is set.
+        let mut steps = 0;
+        while bits(ecx.stack()[0].locals[1]).is_none() {
+            assert!(ecx.step().unwrap());
+            steps += 1;
+            assert!(steps < 100, "`y` was never assigned");
+        }
+        assert_eq!(ecx.stack().len(), 1);
+        assert_eq!(bits(ecx.stack()[0].locals[0]), Some(1));
+        assert_eq!(bits(ecx.stack()[0].locals[1]), Some(1));
+        assert_eq!(bits(ecx.stack()[0].locals[2]), None);
+
+        while ecx.step().unwrap() {}
+        assert!(ecx.stack().is_empty());
+    }
+    helpers::run("straight_line", check);
+}
+
+#[test]
+fn mir_for() {
+    fn check<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir_map: &'a MirMap<'tcx>, main: DefId) {
+        let ecx = EvalContext::new(tcx, mir_map, MEMORY_SIZE, STACK_LIMIT);
+        // `main` has no branches or calls, so all of it is in a single block.
+        let mir = ecx.mir_for(main).expect("no MIR for `main`");
+        assert_eq!(mir.basic_blocks().len(), 1);
+    }
+    helpers::run("straight_line", check);
+}
+
+#[test]
+fn relocations_in_range() {
+    fn check<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir_map: &'a MirMap<'tcx>, _: DefId) {
+        let mut ecx = EvalContext::new(tcx, mir_map, MEMORY_SIZE, STACK_LIMIT);
+        let memory = ecx.memory_mut();
+        let ptr_size = memory.pointer_size();
+        let target = memory.allocate(1, 1).unwrap();
+        let other_target = memory.allocate(1, 1).unwrap();
+        let ptr = memory.allocate(3 * ptr_size, ptr_size).unwrap();
+        memory.write_ptr(ptr, target).unwrap();
+        memory.write_ptr(ptr.offset(2 * ptr_size as isize), other_target).unwrap();
+
+        assert_eq!(memory.relocations_in_range(ptr, 3 * ptr_size).unwrap(),
+                   vec![(0, target.alloc_id), (2 * ptr_size, other_target.alloc_id)]);
+        // The first pointer overlaps this range, but starts before it.
+        assert_eq!(memory.relocations_in_range(ptr.offset(1), 2 * ptr_size).unwrap(),
+                   vec![(2 * ptr_size - 1, other_target.alloc_id)]);
+        // This range ends right before the second pointer.
+        assert!(memory.relocations_in_range(ptr.offset(1), 2 * ptr_size - 1).unwrap().is_empty());
+    }
+    helpers::run("empty", check);
+}
+
+#[test]
+fn allocation_relocations() {
+    fn check<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir_map: &'a MirMap<'tcx>, _: DefId) {
+        let mut ecx = EvalContext::new(tcx, mir_map, MEMORY_SIZE, STACK_LIMIT);
+        let memory = ecx.memory_mut();
+        let ptr_size = memory.pointer_size();
+        let target = memory.allocate(1, 1).unwrap();
+        let ptr = memory.allocate(2 * ptr_size, ptr_size).unwrap();
+        // Written out of order, but iterated by offset.
+        memory.write_ptr(ptr.offset(ptr_size as isize), target).unwrap();
+        memory.write_ptr(ptr, ptr).unwrap();
+
+        let relocations: Vec<_> = memory.get(ptr.alloc_id).unwrap().relocations().collect();
+        assert_eq!(relocations, vec![(0, ptr.alloc_id), (ptr_size, target.alloc_id)]);
+    }
+    helpers::run("empty", check);
+}
+
+#[test]
+fn main_result() {
+    fn check<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir_map: &'a MirMap<'tcx>, start: DefId) {
+        let result = miri::eval_main_with_result(tcx, mir_map, start, &EvalConfig::default())
+            .expect("`start` returned no value");
+        match result.value {
+            Value::ByVal(val) => assert_eq!(val.bits, 42),
+            value => panic!("expected a primitive value, got {:?}", value),
+        }
+        assert_eq!(result.formatted, "42");
+    }
+    helpers::run("start", check);
+}
@@ -0,0 +1,26 @@
+#![feature(const_fn)]
+#![allow(dead_code)]
+
+const fn double(x: usize) -> usize {
+    x * 2
+}
+
+fn boxed() -> usize {
+    *Box::new(3)
+}
+
+static mut COUNTER: usize = 0;
+static LIMIT: usize = 5;
+
+fn bump() -> usize {
+    unsafe {
+        COUNTER += 1;
+        COUNTER
+    }
+}
+
+fn read_limit() -> usize {
+    LIMIT
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+#![allow(dead_code)]
+
+const X: u32 = 2 * 21;
+
+mod inner {
+    pub const Y: u64 = super::X as u64 + 1;
+}
+
+fn main() {}
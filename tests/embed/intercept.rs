@@ -0,0 +1,17 @@
+#![allow(dead_code)]
+
+fn helper(x: u32) -> u32 {
+    x + 1
+}
+
+fn stop() -> ! {
+    panic!("stop")
+}
+
+fn main() {
+    assert_eq!(helper(1), 42);
+}
+
+fn diverge() {
+    stop();
+}
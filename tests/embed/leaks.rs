@@ -0,0 +1,12 @@
+#![allow(dead_code)]
+
+fn balanced() {
+    let b = Box::new(1u32);
+    assert_eq!(*b, 1);
+}
+
+fn forget() {
+    std::mem::forget(Box::new(2u32));
+}
+
+fn main() {}
@@ -0,0 +1,23 @@
+#![allow(dead_code)]
+
+extern "C" {
+    fn write(fd: i32, buf: *const u8, count: usize) -> isize;
+}
+
+fn first() {
+    println!("first");
+}
+
+fn second() {
+    println!("second");
+}
+
+fn both_streams() {
+    unsafe {
+        write(1, b"out\n".as_ptr(), 4);
+        write(2, b"err\n".as_ptr(), 4);
+        write(1, b"out again\n".as_ptr(), 10);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+#![allow(dead_code)]
+
+static mut COUNTER: u32 = 0;
+
+fn bump() {
+    unsafe {
+        COUNTER += 1;
+        println!("{}", COUNTER);
+    }
+}
+
+fn greet() {
+    println!("{}", "hello");
+}
+
+fn main() {}
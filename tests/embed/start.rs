@@ -0,0 +1,6 @@
+#![feature(start)]
+
+#[start]
+fn start(_: isize, _: *const *const u8) -> isize {
+    6 * 7
+}
@@ -0,0 +1,8 @@
+static mut RUNS: u32 = 0;
+
+fn main() {
+    unsafe {
+        RUNS += 1;
+        println!("run {}", RUNS);
+    }
+}
@@ -0,0 +1,5 @@
+fn main() {
+    let x = 1u32;
+    let y = x & 3;
+    let _z = (y | 4, x ^ y);
+}
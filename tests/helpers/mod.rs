@@ -0,0 +1,91 @@
+// This module gets included in multiple crates, and they each only use part of it.
+#![allow(dead_code)]
+
+extern crate getopts;
+extern crate miri;
+extern crate rustc;
+extern crate rustc_driver;
+
+use self::miri::run_mir_passes;
+use self::rustc::hir::def_id::DefId;
+use self::rustc::mir::mir_map::MirMap;
+use self::rustc::session::Session;
+use self::rustc::ty::TyCtxt;
+use self::rustc_driver::{driver, CompilerCalls, Compilation};
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// A test of the library API. It gets the MIR of the compiled program and its `main` function.
+pub type Check = for<'a, 'tcx> fn(TyCtxt<'a, 'tcx, 'tcx>, &'a MirMap<'tcx>, DefId);
+
+struct MiriCompilerCalls {
+    check: Check,
+    ran: Rc<Cell<bool>>,
+}
+
+fn find_sysroot() -> String {
+    // Taken from https://github.com/Manishearth/rust-clippy/pull/911.
+    let home = option_env!("RUSTUP_HOME").or(option_env!("MULTIRUST_HOME"));
+    let toolchain = option_env!("RUSTUP_TOOLCHAIN").or(option_env!("MULTIRUST_TOOLCHAIN"));
+    match (home, toolchain) {
+        (Some(home), Some(toolchain)) => format!("{}/toolchains/{}", home, toolchain),
+        _ => option_env!("RUST_SYSROOT")
+            .expect("need to specify RUST_SYSROOT env var or use rustup or multirust")
+            .to_owned(),
+    }
+}
+
+/// Compiles `tests/embed/<name>.rs` and runs `check` on it.
+pub fn run(name: &str, check: Check) {
+    let args = &[
+        "miri".to_string(),
+        format!("tests/embed/{}.rs", name),
+        "--sysroot".to_string(),
+        find_sysroot(),
+    ];
+    let ran = Rc::new(Cell::new(false));
+    let compiler_calls = &mut MiriCompilerCalls { check: check, ran: ran.clone() };
+    rustc_driver::run_compiler(args, compiler_calls, None, None);
+    assert!(ran.get(), "tests/embed/{}.rs failed to compile", name);
+}
+
+/// Finds the function with the path `name` (as printed by `TyCtxt::item_path_str`).
+pub fn find_fn<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir_map: &MirMap<'tcx>, name: &str) -> DefId {
+    mir_map.map.keys().into_iter()
+        .find(|&def_id| tcx.item_path_str(def_id) == name)
+        .unwrap_or_else(|| panic!("no function `{}`", name))
+}
+
+impl<'a> CompilerCalls<'a> for MiriCompilerCalls {
+    fn build_controller(
+        &mut self,
+        _: &Session,
+        _: &getopts::Matches
+    ) -> driver::CompileController<'a> {
+        let mut control = driver::CompileController::basic();
+
+        let check = self.check;
+        let ran = self.ran.clone();
+
+        control.after_analysis.stop = Compilation::Stop;
+        control.after_analysis.callback = Box::new(move |state| {
+            state.session.abort_if_errors();
+
+            let tcx = state.tcx.unwrap();
+            let mir_map = state.mir_map.unwrap();
+            let (entry_node_id, _) = state.session.entry_fn.borrow()
+                .expect("no main or start function found");
+            let entry_def_id = tcx.map.local_def_id(entry_node_id);
+
+            let mut mir_map_copy = MirMap::new(tcx.dep_graph.clone());
+            for def_id in mir_map.map.keys() {
+                mir_map_copy.map.insert(def_id, mir_map.map.get(&def_id).unwrap().clone());
+            }
+            run_mir_passes(tcx, &mut mir_map_copy);
+            check(tcx, &mir_map_copy, entry_def_id);
+            ran.set(true);
+        });
+
+        control
+    }
+}
@@ -0,0 +1,27 @@
+const P: (u8, u32) = (1, 2);
+const S: &'static str = "xyz";
+const A: [u16; 3] = [10, 20, 30];
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+const ORIGIN: Point = Point { x: -1, y: 7 };
+
+fn main() {
+    assert_eq!(P.0, 1);
+    assert_eq!(P.1, 2);
+    let p = P;
+    assert_eq!(p, (1, 2));
+
+    assert_eq!(S.len(), 3);
+    assert_eq!(S.as_bytes(), b"xyz");
+    assert_eq!(S.as_bytes()[2], b'z');
+
+    assert_eq!(A[1], 20);
+    assert_eq!(A.iter().fold(0, |acc, &x| acc + x), 60);
+
+    assert_eq!(ORIGIN.x, -1);
+    assert_eq!(ORIGIN.y, 7);
+}
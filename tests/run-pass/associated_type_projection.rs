@@ -0,0 +1,38 @@
+trait Container {
+    type Item;
+    fn get(&self, i: usize) -> Self::Item;
+}
+
+struct Pair(u8, u64);
+
+impl Container for Pair {
+    type Item = (u8, u64);
+    fn get(&self, _: usize) -> (u8, u64) {
+        (self.0, self.1)
+    }
+}
+
+fn first<I: Iterator>(mut iter: I) -> Option<I::Item> {
+    iter.next()
+}
+
+fn sum<I: Iterator<Item = u32>>(iter: I) -> u32 {
+    let mut total = 0;
+    for x in iter {
+        total += x;
+    }
+    total
+}
+
+// The layout of `C::Item` is only known after normalizing the projection.
+fn get_twice<C: Container>(c: &C) -> (C::Item, C::Item) {
+    (c.get(0), c.get(1))
+}
+
+fn main() {
+    let v = [1u32, 2, 3];
+    assert_eq!(first(v.iter()), Some(&1));
+    assert_eq!(first((0..0u8).map(|x| x as u64)), None);
+    assert_eq!(sum(v.iter().cloned()), 6);
+    assert_eq!(get_twice(&Pair(1, 2)), ((1, 2), (1, 2)));
+}
@@ -0,0 +1,19 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn main() {
+    let x = AtomicUsize::new(5);
+    assert_eq!(x.load(Ordering::SeqCst), 5);
+
+    // successful compare-exchange
+    assert_eq!(x.compare_and_swap(5, 10, Ordering::SeqCst), 5);
+    assert_eq!(x.load(Ordering::SeqCst), 10);
+
+    // failing compare-exchange leaves the value untouched
+    assert_eq!(x.compare_and_swap(5, 20, Ordering::SeqCst), 10);
+    assert_eq!(x.load(Ordering::SeqCst), 10);
+
+    assert_eq!(x.swap(3, Ordering::SeqCst), 10);
+    assert_eq!(x.fetch_add(4, Ordering::SeqCst), 3);
+    assert_eq!(x.fetch_sub(2, Ordering::SeqCst), 7);
+    assert_eq!(x.load(Ordering::SeqCst), 5);
+}
@@ -0,0 +1,26 @@
+#![feature(custom_attribute, attr_literals)]
+#![miri(memory_size=1000)]
+
+// Moving out of a box frees the box itself exactly once, while the moved-out value lives on. If
+// either allocation leaked, this would run out of memory.
+
+fn unbox(b: Box<Box<u64>>) -> Box<u64> {
+    *b
+}
+
+fn main() {
+    let mut i = 0;
+    let mut sum = 0;
+    while i < 200 {
+        let outer = Box::new(Box::new(i));
+        let inner = unbox(outer);
+        sum += *inner;
+        i += 1;
+    }
+    assert_eq!(sum, 199 * 200 / 2);
+
+    let b = Box::new((1u8, Box::new(2u32)));
+    let (x, y) = *b;
+    assert_eq!(x, 1);
+    assert_eq!(*y, 2);
+}
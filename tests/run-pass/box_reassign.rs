@@ -0,0 +1,26 @@
+#![feature(custom_attribute, attr_literals)]
+#![miri(memory_size=1000, check_leaks=true)]
+
+// Every reassignment must free the previous box, otherwise this runs out of memory or leaks.
+
+struct Holder {
+    b: Box<u64>,
+}
+
+fn main() {
+    let mut b = Box::new(0u64);
+    let mut i = 0;
+    while i < 1000 {
+        b = Box::new(i);
+        i += 1;
+    }
+    assert_eq!(*b, 999);
+
+    let mut h = Holder { b: Box::new(0) };
+    let mut i = 0;
+    while i < 1000 {
+        h.b = Box::new(i);
+        i += 1;
+    }
+    assert_eq!(*h.b, 999);
+}
@@ -0,0 +1,13 @@
+extern {
+    fn strlen(s: *const u8) -> usize;
+}
+
+fn main() {
+    unsafe {
+        assert_eq!(strlen(b"hello\0".as_ptr()), 5);
+        assert_eq!(strlen(b"\0".as_ptr()), 0);
+        // Only the bytes up to the first NUL count.
+        assert_eq!(strlen(b"ab\0cd\0".as_ptr()), 2);
+        assert_eq!(strlen(b"hello\0".as_ptr().offset(3)), 2);
+    }
+}
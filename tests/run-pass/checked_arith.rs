@@ -0,0 +1,17 @@
+fn main() {
+    // the result pair stays in a local
+    let (val, overflowed) = 255u8.overflowing_add(1);
+    assert_eq!(val, 0);
+    assert!(overflowed);
+
+    // the result pair is read back from memory, where the flag lives at the tuple's field 1 offset
+    let t = u64::max_value().overflowing_add(2);
+    let r = &t;
+    assert_eq!(r.0, 1);
+    assert!(r.1);
+
+    let s = 200u16.overflowing_sub(100);
+    let r = &s;
+    assert_eq!(r.0, 100);
+    assert!(!r.1);
+}
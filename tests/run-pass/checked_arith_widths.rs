@@ -0,0 +1,21 @@
+#![feature(core_intrinsics)]
+
+use std::intrinsics::{add_with_overflow, mul_with_overflow, sub_with_overflow};
+
+fn main() {
+    // unsigned results wrap around at the width of the type
+    assert_eq!(unsafe { add_with_overflow(250u8, 10) }, (4, true));
+    assert_eq!(unsafe { mul_with_overflow(0x1_0000u32, 0x1_0000) }, (0, true));
+
+    // signed overflow is detected at the sign change, not at the unsigned wrap
+    assert_eq!(unsafe { add_with_overflow(127i8, 1) }, (-128, true));
+    assert_eq!(unsafe { sub_with_overflow(-128i8, 1) }, (127, true));
+    assert_eq!(unsafe { add_with_overflow(-1i8, -1) }, (-2, false));
+    assert_eq!(unsafe { mul_with_overflow(i64::min_value(), -1) }, (i64::min_value(), true));
+
+    // results that fit are left alone
+    assert_eq!(unsafe { add_with_overflow(100u8, 27) }, (127, false));
+    assert_eq!(unsafe { sub_with_overflow(5i16, 7) }, (-2, false));
+    assert_eq!(255u8.checked_add(1), None);
+    assert_eq!(100i8.checked_add(27), Some(127));
+}
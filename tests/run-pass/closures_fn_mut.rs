@@ -0,0 +1,23 @@
+fn call_thrice<F: FnMut() -> i32>(mut f: F) -> (i32, i32, i32) {
+    (f(), f(), f())
+}
+
+fn counter_by_ref() -> (i32, i32, i32, i32) {
+    let mut count = 0;
+    let results = {
+        let counter = || { count += 1; count };
+        call_thrice(counter)
+    };
+    (results.0, results.1, results.2, count)
+}
+
+fn counter_by_value() -> (i32, i32, i32) {
+    let mut count = 0;
+    let mut counter = move || { count += 1; count };
+    (counter(), counter(), counter())
+}
+
+fn main() {
+    assert_eq!(counter_by_ref(), (1, 2, 3, 3));
+    assert_eq!(counter_by_value(), (1, 2, 3));
+}
@@ -0,0 +1,14 @@
+#![feature(const_fn)]
+
+const fn double(x: usize) -> usize {
+    x * 2
+}
+
+const LEN: usize = double(3);
+
+fn main() {
+    let arr = [0u8; double(2)];
+    assert_eq!(arr.len(), 4);
+    assert_eq!(LEN, 6);
+    assert_eq!(double(21), 42);
+}
@@ -0,0 +1,17 @@
+use std::ptr;
+
+fn main() {
+    let (a, b, c) = (1, 2, 3);
+    let mut refs: [&i32; 4] = [&a, &b, &c, &c];
+    unsafe {
+        // shift the pointers one slot to the right, like `Vec::insert` does
+        let p = refs.as_mut_ptr();
+        ptr::copy(p, p.offset(1), 3);
+    }
+    assert_eq!((*refs[0], *refs[1], *refs[2], *refs[3]), (1, 1, 2, 3));
+
+    let mut v = vec![&a, &b, &c];
+    v.insert(0, &c);
+    v.remove(2);
+    assert_eq!((*v[0], *v[1], *v[2]), (3, 1, 3));
+}
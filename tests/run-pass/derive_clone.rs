@@ -0,0 +1,42 @@
+#[derive(Clone, Debug, PartialEq)]
+struct Inner {
+    x: u8,
+    b: Box<i32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Outer {
+    inner: Inner,
+    opt: Option<Box<u64>>,
+    pair: (Box<i32>, u16),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum E {
+    A(u8),
+    B { y: i16 },
+}
+
+fn main() {
+    let a = Outer {
+        inner: Inner { x: 1, b: Box::new(42) },
+        opt: Some(Box::new(7)),
+        pair: (Box::new(-3), 9),
+    };
+    let c = a.clone();
+    assert_eq!(a, c);
+
+    // the boxes are deep-copied into fresh heap allocations
+    assert!(&*a.inner.b as *const i32 != &*c.inner.b as *const i32);
+    assert!(&*a.pair.0 as *const i32 != &*c.pair.0 as *const i32);
+    assert_eq!(*c.inner.b, 42);
+    assert_eq!(c.opt, Some(Box::new(7)));
+
+    let mut d = c.clone();
+    *d.inner.b = 5;
+    assert_eq!(*c.inner.b, 42);
+    assert_eq!(*d.inner.b, 5);
+
+    assert_eq!(E::A(3).clone(), E::A(3));
+    assert_eq!(E::B { y: -1 }.clone(), E::B { y: -1 });
+}
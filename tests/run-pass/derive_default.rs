@@ -0,0 +1,34 @@
+#[derive(Default, Debug, PartialEq)]
+struct Inner {
+    flag: bool,
+    c: char,
+}
+
+#[derive(Default, Debug, PartialEq)]
+struct MyStruct {
+    a: u8,
+    b: i64,
+    inner: Inner,
+    opt: Option<Box<u32>>,
+    pair: (u16, f64),
+}
+
+#[derive(Default)]
+struct Unit;
+
+#[derive(Default)]
+struct Tuple(i32, u32);
+
+fn main() {
+    let s = MyStruct::default();
+    assert_eq!(s.a, 0);
+    assert_eq!(s.b, 0);
+    assert_eq!(s.inner, Inner { flag: false, c: '\0' });
+    assert!(s.opt.is_none());
+    assert_eq!(s.pair.0, 0);
+    assert_eq!(s.pair.1, 0.0);
+
+    let _: Unit = Default::default();
+    let t: Tuple = Default::default();
+    assert_eq!((t.0, t.1), (0, 0));
+}
@@ -0,0 +1,37 @@
+#[derive(PartialEq, Debug)]
+struct Inner {
+    x: i32,
+    y: u8,
+}
+
+#[derive(PartialEq, Debug)]
+enum Shape {
+    Point,
+    Circle(u32),
+    Rect { w: u16, h: u16 },
+}
+
+#[derive(PartialEq, Debug)]
+struct Outer {
+    a: Inner,
+    b: Inner,
+    shape: Shape,
+}
+
+fn outer(x: i32, shape: Shape) -> Outer {
+    Outer {
+        a: Inner { x: x, y: 1 },
+        b: Inner { x: -x, y: 2 },
+        shape: shape,
+    }
+}
+
+fn main() {
+    assert!(outer(5, Shape::Point) == outer(5, Shape::Point));
+    assert!(outer(5, Shape::Point) != outer(6, Shape::Point));
+    assert!(outer(5, Shape::Circle(3)) == outer(5, Shape::Circle(3)));
+    assert!(outer(5, Shape::Circle(3)) != outer(5, Shape::Circle(4)));
+    assert!(outer(5, Shape::Circle(3)) != outer(5, Shape::Point));
+    assert!(outer(1, Shape::Rect { w: 1, h: 2 }) == outer(1, Shape::Rect { w: 1, h: 2 }));
+    assert!(outer(1, Shape::Rect { w: 1, h: 2 }) != outer(1, Shape::Rect { w: 2, h: 1 }));
+}
@@ -0,0 +1,14 @@
+#![feature(custom_attribute, attr_literals)]
+#![miri(deterministic_addresses=true)]
+
+fn main() {
+    let a = 1i32;
+    let b = 2i32;
+    let pa = &a as *const i32 as usize;
+    let pb = &b as *const i32 as usize;
+    assert!(pa != pb);
+    assert!(pa != 0 && pb != 0);
+    // the address of an allocation never changes
+    assert_eq!(pa, &a as *const i32 as usize);
+    assert_eq!(pa % std::mem::align_of::<i32>(), 0);
+}
@@ -0,0 +1,23 @@
+#![feature(custom_attribute, attr_literals)]
+#![miri(memory_size=1000)]
+
+// Every box inside these aggregates has to be freed when the aggregate is dropped, otherwise this
+// runs out of memory.
+
+struct Pair {
+    a: Box<u32>,
+    b: Box<u32>,
+}
+
+fn main() {
+    let mut i = 0;
+    let mut sum = 0;
+    while i < 100 {
+        let array = [Box::new(i), Box::new(i + 1), Box::new(i + 2)];
+        let tuple = (Box::new(1u32), 2u8, Box::new(3u32));
+        let pair = Pair { a: Box::new(4), b: Box::new(5) };
+        sum += *array[2] + *tuple.0 + *tuple.2 + *pair.a + *pair.b;
+        i += 1;
+    }
+    assert_eq!(sum, 6450);
+}
@@ -0,0 +1,37 @@
+// Every drop appends its id as a decimal digit, so the log reads in drop order.
+static mut LOG: u32 = 0;
+
+struct Logged(u32);
+
+impl Drop for Logged {
+    fn drop(&mut self) {
+        unsafe { LOG = LOG * 10 + self.0; }
+    }
+}
+
+struct Triple {
+    _a: Logged,
+    _b: Logged,
+    _c: Logged,
+}
+
+fn take_log() -> u32 {
+    unsafe {
+        let log = LOG;
+        LOG = 0;
+        log
+    }
+}
+
+fn main() {
+    // tuple fields and struct fields are dropped in declaration order
+    drop((Logged(1), Logged(2), Logged(3)));
+    assert_eq!(take_log(), 123);
+
+    drop(Triple { _c: Logged(3), _a: Logged(1), _b: Logged(2) });
+    assert_eq!(take_log(), 123);
+
+    // array elements are dropped from the first to the last
+    drop([Logged(1), Logged(2), Logged(3)]);
+    assert_eq!(take_log(), 123);
+}
@@ -0,0 +1,48 @@
+static mut DROPPED: u32 = 0;
+
+struct Sentinel(u32);
+
+impl Drop for Sentinel {
+    fn drop(&mut self) {
+        unsafe { DROPPED += self.0; }
+    }
+}
+
+struct Outer {
+    _inner: Sentinel,
+}
+
+impl Drop for Outer {
+    fn drop(&mut self) {
+        // The fields are dropped after this returns.
+        unsafe { assert_eq!(DROPPED, 0); }
+    }
+}
+
+fn make_and_drop() {
+    let _sentinel = Sentinel(1);
+}
+
+fn move_out(s: Sentinel) -> Sentinel {
+    s
+}
+
+fn main() {
+    make_and_drop();
+    unsafe { assert_eq!(DROPPED, 1); }
+
+    // A value that was moved out of a frame must not be dropped by it.
+    let s = move_out(Sentinel(1));
+    unsafe { assert_eq!(DROPPED, 1); }
+    drop(s);
+    unsafe { assert_eq!(DROPPED, 2); }
+
+    unsafe { DROPPED = 0; }
+    drop(Outer { _inner: Sentinel(1) });
+    unsafe { assert_eq!(DROPPED, 1); }
+
+    unsafe { DROPPED = 0; }
+    let v = vec![Sentinel(1), Sentinel(10), Sentinel(100)];
+    drop(v);
+    unsafe { assert_eq!(DROPPED, 111); }
+}
@@ -0,0 +1,38 @@
+use std::mem;
+
+struct S<T: ?Sized> {
+    len: usize,
+    flag: u8,
+    data: T,
+}
+
+fn sum(s: &S<[u8]>) -> usize {
+    assert_eq!(s.len, s.data.len());
+    s.data.iter().fold(0, |acc, &x| acc + x as usize)
+}
+
+fn last(s: &S<[u16]>) -> u16 {
+    s.data[s.data.len() - 1]
+}
+
+fn main() {
+    let s = S { len: 3, flag: 7, data: [1u8, 2, 3] };
+    let r: &S<[u8]> = &s;
+    assert_eq!(r.len, 3);
+    assert_eq!(r.flag, 7);
+    assert_eq!(r.data[2], 3);
+    assert_eq!(sum(r), 6);
+    assert_eq!(mem::size_of_val(r), mem::size_of::<S<[u8; 3]>>());
+
+    let b: Box<S<[u16]>> = Box::new(S { len: 2, flag: 0, data: [10u16, 20] });
+    assert_eq!(last(&b), 20);
+    assert_eq!(&b.data, &[10, 20]);
+
+    let mut m = S { len: 1, flag: 1, data: [5u8] };
+    {
+        let r: &mut S<[u8]> = &mut m;
+        r.data[0] = 9;
+        r.flag = 2;
+    }
+    assert_eq!((m.flag, m.data[0]), (2, 9));
+}
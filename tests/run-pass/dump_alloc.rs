@@ -0,0 +1,17 @@
+// Miri prints the allocation behind the pointer instead of calling this function.
+#[no_mangle]
+pub extern "C" fn miri_dump_alloc(_: *const u8) {}
+
+#[repr(C)]
+struct S<'a> {
+    a: u8,
+    b: u8,
+    p: &'a u8,
+}
+
+fn main() {
+    let x = 5u8;
+    let s = S { a: 1, b: 2, p: &x };
+    miri_dump_alloc(&s as *const S as *const u8);
+    assert_eq!(*s.p + s.a + s.b, 8);
+}
@@ -0,0 +1,42 @@
+use std::{f32, f64};
+use std::mem::transmute;
+
+fn roundtrip_f32(bits: u32) -> u32 {
+    let f: f32 = unsafe { transmute(bits) };
+    // store and reload the float through memory
+    let boxed = Box::new(f);
+    unsafe { transmute(*boxed) }
+}
+
+fn roundtrip_f64(bits: u64) -> u64 {
+    let f: f64 = unsafe { transmute(bits) };
+    let arr = [f, 1.0];
+    unsafe { transmute(arr[0]) }
+}
+
+fn main() {
+    let zero: u64 = unsafe { transmute(0.0f64) };
+    let neg_zero: u64 = unsafe { transmute(-0.0f64) };
+    assert_eq!(zero, 0);
+    assert_eq!(neg_zero, 0x8000_0000_0000_0000);
+    assert_eq!(roundtrip_f64(neg_zero), neg_zero);
+
+    let nan: u64 = unsafe { transmute(f64::NAN) };
+    assert_eq!(roundtrip_f64(nan), nan);
+    // a signaling NaN and a NaN with a payload keep their exact bits
+    assert_eq!(roundtrip_f64(0x7ff0_0000_0000_0001), 0x7ff0_0000_0000_0001);
+    assert_eq!(roundtrip_f32(0x7fa0_0001), 0x7fa0_0001);
+    assert_eq!(roundtrip_f32(0xffc0_1234), 0xffc0_1234);
+
+    // the smallest subnormals
+    assert_eq!(roundtrip_f64(1), 1);
+    assert_eq!(roundtrip_f32(1), 1);
+    let sub: f64 = unsafe { transmute(1u64) };
+    assert!(sub > 0.0 && sub < f64::MIN_POSITIVE);
+
+    assert_eq!((-4.0f64).abs(), 4.0);
+    assert_eq!(9.0f64.sqrt(), 3.0);
+    assert_eq!(2.0f64.powi(3), 8.0);
+    assert_eq!(-0.0f32, 0.0f32);
+    assert!(f32::NAN != f32::NAN);
+}
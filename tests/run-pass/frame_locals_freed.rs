@@ -0,0 +1,22 @@
+#![feature(custom_attribute, attr_literals)]
+#![miri(memory_size=1000)]
+
+// Taking a reference forces `buf` into an allocation, which has to be freed when the frame is
+// popped, otherwise this runs out of memory.
+
+fn sum(buf: &[u8; 100]) -> u32 {
+    buf.iter().map(|&b| b as u32).sum()
+}
+
+fn fill(x: u8) -> u32 {
+    let buf = [x; 100];
+    sum(&buf)
+}
+
+fn main() {
+    let mut total = 0;
+    for i in 0..50 {
+        total += fill(i);
+    }
+    assert_eq!(total, 122500);
+}
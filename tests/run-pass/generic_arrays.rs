@@ -0,0 +1,25 @@
+// Const generics aren't supported by this compiler, but the layout of arrays still depends on
+// substitutions through their element type.
+use std::mem::size_of;
+
+fn repeat<T: Copy>(x: T) -> [T; 4] {
+    [x; 4]
+}
+
+fn empty<T>() -> [T; 0] {
+    []
+}
+
+fn main() {
+    let a = repeat(7u8);
+    assert_eq!(a, [7; 4]);
+    assert_eq!(size_of::<[u8; 4]>(), 4);
+    let b = repeat(0x1234u16);
+    assert_eq!(b[3], 0x1234);
+    let c = repeat((1u8, 2u32));
+    assert_eq!(c[2].1, 2);
+
+    let e: [u64; 0] = empty();
+    assert_eq!(e.len(), 0);
+    assert_eq!(size_of::<[u64; 0]>(), 0);
+}
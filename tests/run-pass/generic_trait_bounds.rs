@@ -0,0 +1,47 @@
+trait Shape {
+    fn area(&self) -> u32;
+    fn sides() -> u32;
+    fn describe(&self) -> (u32, u32) {
+        (Self::sides(), self.area())
+    }
+}
+
+struct Square(u32);
+struct Rect(u32, u32);
+
+impl Shape for Square {
+    fn area(&self) -> u32 { self.0 * self.0 }
+    fn sides() -> u32 { 4 }
+}
+
+impl Shape for Rect {
+    fn area(&self) -> u32 { self.0 * self.1 }
+    fn sides() -> u32 { 4 }
+    fn describe(&self) -> (u32, u32) {
+        (0, self.area())
+    }
+}
+
+fn area<T: Shape>(x: T) -> u32 {
+    x.area()
+}
+
+fn describe<T>(x: &T) -> (u32, u32) where T: Shape {
+    x.describe()
+}
+
+fn total_area<T: Shape, U: Shape>(a: &T, b: &U) -> u32 {
+    area_ref(a) + area_ref(b)
+}
+
+fn area_ref<T: Shape>(x: &T) -> u32 {
+    x.area()
+}
+
+fn main() {
+    assert_eq!(area(Square(3)), 9);
+    assert_eq!(area(Rect(2, 5)), 10);
+    assert_eq!(describe(&Square(2)), (4, 4));
+    assert_eq!(describe(&Rect(2, 3)), (0, 6));
+    assert_eq!(total_area(&Square(1), &Rect(3, 3)), 10);
+}
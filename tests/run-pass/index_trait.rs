@@ -0,0 +1,43 @@
+use std::ops::{Index, IndexMut};
+
+struct Grid {
+    width: usize,
+    cells: Vec<i32>,
+}
+
+impl Index<(usize, usize)> for Grid {
+    type Output = i32;
+    fn index(&self, (x, y): (usize, usize)) -> &i32 {
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Grid {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut i32 {
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+struct Reversed<'a>(&'a [u8]);
+
+impl<'a> Index<usize> for Reversed<'a> {
+    type Output = u8;
+    fn index(&self, i: usize) -> &u8 {
+        &self.0[self.0.len() - 1 - i]
+    }
+}
+
+fn main() {
+    let mut grid = Grid { width: 3, cells: vec![0, 1, 2, 3, 4, 5] };
+    assert_eq!(grid[(0, 0)], 0);
+    assert_eq!(grid[(2, 0)], 2);
+    assert_eq!(grid[(1, 1)], 4);
+    grid[(2, 1)] = 50;
+    grid[(0, 1)] += 30;
+    assert_eq!(grid.cells, [0, 1, 2, 33, 4, 50]);
+
+    let data = [1, 2, 3];
+    let r = Reversed(&data);
+    assert_eq!(r[0], 3);
+    assert_eq!(r[2], 1);
+}
@@ -0,0 +1,14 @@
+fn get(a: &[u16], i: usize) -> u16 {
+    a[i]
+}
+
+fn main() {
+    let array = [10u16, 20, 30, 40];
+    let i = array.len() - 1;
+    assert_eq!(array[i], 40);
+    assert_eq!(array[0], 10);
+    assert_eq!(get(&array, 2), 30);
+    assert_eq!(get(&array[1..3], 1), 30);
+    let v = vec![1u64, 2, 3];
+    assert_eq!(v[2], 3);
+}
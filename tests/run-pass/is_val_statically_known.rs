@@ -0,0 +1,20 @@
+#![feature(core_intrinsics)]
+
+use std::intrinsics::is_val_statically_known;
+
+fn pow2(n: u32) -> u32 {
+    if unsafe { is_val_statically_known(n) } {
+        panic!("miri never knows a value statically");
+    } else {
+        let mut r = 1;
+        for _ in 0..n {
+            r *= 2;
+        }
+        r
+    }
+}
+
+fn main() {
+    assert_eq!(pow2(0), 1);
+    assert_eq!(pow2(5), 32);
+}
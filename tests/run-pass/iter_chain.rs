@@ -0,0 +1,18 @@
+fn map_sum() -> i32 {
+    [1, 2, 3].iter().map(|x| x * 2).sum()
+}
+
+fn filter_count() -> usize {
+    [1, 2, 3, 4, 5].iter().filter(|&&x| x % 2 == 1).count()
+}
+
+fn fold_captures() -> i32 {
+    let offset = 10;
+    [1, 2, 3].iter().map(|&x| x + offset).fold(0, |acc, x| acc + x)
+}
+
+fn main() {
+    assert_eq!(map_sum(), 12);
+    assert_eq!(filter_count(), 3);
+    assert_eq!(fold_captures(), 36);
+}
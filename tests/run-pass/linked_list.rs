@@ -0,0 +1,34 @@
+enum List {
+    Cons(i32, Box<List>),
+    Nil,
+}
+
+use List::*;
+
+fn sum(list: &List) -> i32 {
+    match *list {
+        Cons(x, ref rest) => x + sum(rest),
+        Nil => 0,
+    }
+}
+
+fn len(list: &List) -> usize {
+    let mut n = 0;
+    let mut cur = list;
+    while let Cons(_, ref rest) = *cur {
+        n += 1;
+        cur = rest;
+    }
+    n
+}
+
+fn main() {
+    let list = Cons(1, Box::new(Cons(2, Box::new(Cons(3, Box::new(Nil))))));
+    assert_eq!(sum(&list), 6);
+    assert_eq!(len(&list), 3);
+    // dropping the list recursively frees all of its boxes
+    drop(list);
+
+    let boxed = Box::new(Cons(10, Box::new(Nil)));
+    assert_eq!(sum(&boxed), 10);
+}
@@ -0,0 +1,31 @@
+fn classify(s: &str) -> u8 {
+    match s {
+        "" => 0,
+        "a" => 1,
+        "ab" => 2,
+        "abc" => 3,
+        "hello" => 4,
+        _ => 5,
+    }
+}
+
+fn main() {
+    assert_eq!(classify(""), 0);
+    assert_eq!(classify("a"), 1);
+    assert_eq!(classify("ab"), 2);
+    assert_eq!(classify("abc"), 3);
+    assert_eq!(classify("hello"), 4);
+    assert_eq!(classify("hellp"), 5);
+    assert_eq!(classify("b"), 5);
+
+    let mut count = 0;
+    for word in &["hello", "world", "a", "hello"] {
+        if classify(word) == 4 {
+            count += 1;
+        }
+    }
+    assert_eq!(count, 2);
+
+    let owned = String::from("ab");
+    assert_eq!(classify(&owned), 2);
+}
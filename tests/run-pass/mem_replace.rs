@@ -0,0 +1,23 @@
+use std::mem;
+
+fn main() {
+    let mut b = Box::new(1);
+    // the old box is handed back instead of being freed
+    let old = mem::replace(&mut b, Box::new(2));
+    assert_eq!(*old, 1);
+    assert_eq!(*b, 2);
+
+    let mut v = vec![1, 2, 3];
+    let old_v = mem::replace(&mut v, Vec::new());
+    assert_eq!(old_v, [1, 2, 3]);
+    assert!(v.is_empty());
+
+    let mut pair = (Box::new(3u8), 4u64);
+    let old_pair = mem::replace(&mut pair, (Box::new(5), 6));
+    assert_eq!((*old_pair.0, old_pair.1), (3, 4));
+    assert_eq!((*pair.0, pair.1), (5, 6));
+
+    let mut x = 7;
+    mem::swap(&mut x, &mut 8);
+    assert_eq!(x, 8);
+}
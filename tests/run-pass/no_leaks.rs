@@ -0,0 +1,9 @@
+#![feature(custom_attribute, attr_literals)]
+#![miri(check_leaks=true)]
+
+fn main() {
+    let b = Box::new(1u32);
+    let nested = Box::new(Box::new(2u32));
+    let pair = (Box::new(3u8), [Box::new(4u16), Box::new(5)]);
+    assert_eq!(*b + **nested + *pair.0 as u32 + *pair.1[0] as u32 + *pair.1[1] as u32, 15);
+}
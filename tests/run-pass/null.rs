@@ -0,0 +1,19 @@
+use std::ptr;
+
+fn main() {
+    let p: *const i32 = ptr::null();
+    assert!(p.is_null());
+    let q: *mut u8 = ptr::null_mut();
+    assert!(q.is_null());
+
+    let x = 42;
+    let r = &x as *const i32;
+    assert!(!r.is_null());
+    assert!(r != p);
+
+    // pointers to zero-sized values are dangling, but never null
+    let unit = ();
+    assert!(!(&unit as *const ()).is_null());
+    let b = Box::new(5);
+    assert!(!(&*b as *const i32).is_null());
+}
@@ -0,0 +1,21 @@
+// rustc doesn't niche-fill across several variants, so the outer `Option` gets an explicit
+// discriminant while the inner one still uses the null pointer.
+
+fn classify(x: Option<Option<&i32>>) -> i32 {
+    match x {
+        None => -1,
+        Some(None) => 0,
+        Some(Some(&n)) => n,
+    }
+}
+
+fn main() {
+    let n = 42;
+    let values = [None, Some(None), Some(Some(&n))];
+    assert_eq!(classify(values[0]), -1);
+    assert_eq!(classify(values[1]), 0);
+    assert_eq!(classify(values[2]), 42);
+    assert!(values[0].is_none());
+    assert_eq!(values[1], Some(None));
+    assert_eq!(values[2].unwrap().unwrap(), &42);
+}
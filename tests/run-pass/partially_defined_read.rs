@@ -0,0 +1,16 @@
+use std::mem;
+
+fn main() {
+    let mut slot: u64 = unsafe { mem::uninitialized() };
+    let byte = &mut slot as *mut u64 as *mut u8;
+    unsafe {
+        *byte = 42;
+        // only the written byte is defined, so reading just that byte is fine
+        assert_eq!(*byte, 42);
+
+        // copying keeps the definedness of each byte at its new offset
+        let mut copy: [u8; 8] = mem::uninitialized();
+        ::std::ptr::copy_nonoverlapping(byte, copy.as_mut_ptr().offset(3), 5);
+        assert_eq!(copy[3], 42);
+    }
+}
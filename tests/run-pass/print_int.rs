@@ -0,0 +1,7 @@
+fn main() {
+    println!("{}", 123);
+    println!("{} and {:?}", -7i8, 42u64);
+    println!("{} {}", true, "text");
+    print!("{}", 'x');
+    println!("");
+}
@@ -0,0 +1,20 @@
+#![feature(custom_attribute, attr_literals)]
+#![miri(deterministic_addresses=true)]
+
+fn main() {
+    let x = 7u64;
+    let p = &x as *const u64;
+
+    // Every integer type wide enough for an address gives the same one back.
+    let addr = p as usize;
+    assert_eq!(p as u64, addr as u64);
+    assert_eq!(p as isize, addr as isize);
+    assert_eq!(p as i64 as *const u64, p);
+    assert_eq!(unsafe { *(addr as u64 as *const u64) }, 7);
+
+    // Integers that are not the address of any allocation become plain integer pointers.
+    let q = 16usize as *const u8;
+    assert_eq!(q as usize, 16);
+    assert!(!q.is_null());
+    assert!((0usize as *const u8).is_null());
+}
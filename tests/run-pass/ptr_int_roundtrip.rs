@@ -0,0 +1,12 @@
+#![feature(custom_attribute, attr_literals)]
+#![miri(deterministic_addresses=true)]
+
+fn main() {
+    let x = [1u32, 2, 3];
+    let addr = &x[1] as *const u32 as usize;
+    // the address still belongs to `x`, so the pointer regains access to it
+    let p = addr as *const u32;
+    assert_eq!(unsafe { *p }, 2);
+    let q = (addr + 4) as *const u32;
+    assert_eq!(unsafe { *q }, 3);
+}
@@ -0,0 +1,48 @@
+#![feature(question_mark)]
+
+#[derive(Debug, PartialEq)]
+enum ParseError {
+    Empty,
+    Invalid(u8),
+}
+
+#[derive(Debug, PartialEq)]
+struct Error(ParseError);
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Error(e)
+    }
+}
+
+fn digit(b: u8) -> Result<u32, ParseError> {
+    if b >= b'0' && b <= b'9' {
+        Ok((b - b'0') as u32)
+    } else {
+        Err(ParseError::Invalid(b))
+    }
+}
+
+fn parse(s: &[u8]) -> Result<u32, ParseError> {
+    if s.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    let mut n = 0;
+    for &b in s {
+        n = n * 10 + digit(b)?;
+    }
+    Ok(n)
+}
+
+fn sum(a: &[u8], b: &[u8]) -> Result<u32, Error> {
+    Ok(parse(a)? + parse(b)?)
+}
+
+fn main() {
+    assert_eq!(parse(b"42"), Ok(42));
+    assert_eq!(parse(b""), Err(ParseError::Empty));
+    assert_eq!(parse(b"4x2"), Err(ParseError::Invalid(b'x')));
+    assert_eq!(sum(b"12", b"30"), Ok(42));
+    assert_eq!(sum(b"12", b"3a"), Err(Error(ParseError::Invalid(b'a'))));
+    assert_eq!(sum(b"", b"1"), Err(Error(ParseError::Empty)));
+}
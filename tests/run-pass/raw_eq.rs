@@ -0,0 +1,16 @@
+#![feature(core_intrinsics)]
+
+use std::intrinsics::raw_eq;
+
+fn main() {
+    let a = [1u8, 2, 3, 4];
+    let b = [1u8, 2, 3, 4];
+    let c = [1u8, 2, 3, 5];
+    unsafe {
+        assert!(raw_eq(&a, &b));
+        assert!(!raw_eq(&a, &c));
+        assert!(raw_eq(&0x1234_5678u32, &0x1234_5678u32));
+        assert!(!raw_eq(&(1u16, 2u16), &(2u16, 1u16)));
+        assert!(raw_eq(&(), &()));
+    }
+}
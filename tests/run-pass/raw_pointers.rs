@@ -0,0 +1,28 @@
+// The MIR in this compiler has no dedicated address-of rvalue; raw pointers are created by a
+// `Ref` rvalue followed by a pointer cast, which must not leave anything behind but the pointer.
+
+fn read_through_raw_const() -> i32 {
+    let x = 42;
+    let p = &x as *const i32;
+    unsafe { *p }
+}
+
+fn write_through_raw_mut() -> i32 {
+    let mut x = 1;
+    let p = &mut x as *mut i32;
+    unsafe { *p += 41; }
+    x
+}
+
+fn raw_to_field() -> (u8, u8) {
+    let mut t = (1u8, 2u8);
+    let p = &mut t.1 as *mut u8;
+    unsafe { *p = 3; }
+    t
+}
+
+fn main() {
+    assert_eq!(read_through_raw_const(), 42);
+    assert_eq!(write_through_raw_mut(), 42);
+    assert_eq!(raw_to_field(), (1, 3));
+}
@@ -0,0 +1,8 @@
+// Reading a pointer from bytes that were written as an integer yields an integer pointer.
+
+fn main() {
+    let n: usize = 16;
+    let p = unsafe { *(&n as *const usize as *const *const u8) };
+    assert_eq!(p as usize, 16);
+    assert!(!p.is_null());
+}
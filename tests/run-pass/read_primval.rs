@@ -0,0 +1,39 @@
+use std::ptr;
+
+fn roundtrip<T: Copy + PartialEq + std::fmt::Debug>(val: T) {
+    let mut slot = val;
+    unsafe {
+        ptr::write(&mut slot, val);
+        assert_eq!(ptr::read(&slot), val);
+    }
+}
+
+fn id(x: i32) -> i32 { x }
+
+fn main() {
+    roundtrip(-5i8);
+    roundtrip(-300i16);
+    roundtrip(-70000i32);
+    roundtrip(i64::min_value());
+    roundtrip(-1isize);
+    roundtrip(200u8);
+    roundtrip(60000u16);
+    roundtrip(4000000000u32);
+    roundtrip(u64::max_value());
+    roundtrip(usize::max_value());
+    roundtrip(1.5f32);
+    roundtrip(-2.25f64);
+    roundtrip(true);
+    roundtrip('ß');
+
+    let x = 42;
+    let r = &x;
+    let mut slot = r;
+    unsafe { ptr::write(&mut slot, r); }
+    assert_eq!(*unsafe { ptr::read(&slot) }, 42);
+
+    let f: fn(i32) -> i32 = id;
+    let mut slot = f;
+    unsafe { ptr::write(&mut slot, f); }
+    assert_eq!(unsafe { ptr::read(&slot) }(7), 7);
+}
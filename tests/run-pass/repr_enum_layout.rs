@@ -0,0 +1,36 @@
+// An explicit integer representation fixes the size of the discriminant at the start of the enum,
+// with the payload following it.
+
+#[repr(u32)]
+#[allow(dead_code)]
+enum E {
+    A(u8),
+    B(u32, u16),
+    C,
+}
+
+fn discr(e: &E) -> u32 {
+    unsafe { *(e as *const E as *const u32) }
+}
+
+fn main() {
+    let a = E::A(7);
+    let b = E::B(0xdead, 3);
+    let c = E::C;
+    assert_eq!(discr(&a), 0);
+    assert_eq!(discr(&b), 1);
+    assert_eq!(discr(&c), 2);
+
+    unsafe {
+        let pa = &a as *const E as *const u8;
+        assert_eq!(*pa.offset(4), 7);
+        let pb = &b as *const E as *const u32;
+        assert_eq!(*pb.offset(1), 0xdead);
+        assert_eq!(*(pb.offset(2) as *const u16), 3);
+    }
+
+    match b {
+        E::B(x, y) => assert_eq!((x, y), (0xdead, 3)),
+        _ => panic!(),
+    }
+}
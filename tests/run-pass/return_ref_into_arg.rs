@@ -0,0 +1,37 @@
+fn first<T>(s: &[T]) -> &T {
+    &s[0]
+}
+
+fn last<T>(s: &[T]) -> &T {
+    &s[s.len() - 1]
+}
+
+fn field(p: &(u8, u32)) -> &u32 {
+    &p.1
+}
+
+fn larger<'a>(a: &'a i64, b: &'a i64) -> &'a i64 {
+    if *a > *b { a } else { b }
+}
+
+fn first_mut(s: &mut [u16]) -> &mut u16 {
+    &mut s[0]
+}
+
+fn main() {
+    let v = vec![10, 20, 30];
+    assert_eq!(*first(&v), 10);
+    assert_eq!(*last(&v), 30);
+    assert_eq!(*first(&["a", "b"]), "a");
+
+    let p = (1, 2);
+    assert_eq!(*field(&p), 2);
+
+    let (a, b) = (3, 4);
+    assert_eq!(*larger(&a, &b), 4);
+    assert_eq!(*larger(&b, &a), 4);
+
+    let mut arr = [1, 2];
+    *first_mut(&mut arr) = 5;
+    assert_eq!(arr, [5, 2]);
+}
@@ -0,0 +1,31 @@
+// Reading a scalar that directly follows a pointer must not be mistaken for reading part of the
+// pointer, whatever the target's pointer size is.
+
+#[repr(C)]
+struct S<'a> {
+    p: &'a u32,
+    x: u32,
+    y: u8,
+}
+
+#[repr(C)]
+struct T<'a> {
+    a: u8,
+    p: &'a u8,
+}
+
+fn main() {
+    let v = 7;
+    let s = S { p: &v, x: 8, y: 9 };
+    assert_eq!(s.x, 8);
+    assert_eq!(s.y, 9);
+    assert_eq!(*s.p, 7);
+
+    let w = 1;
+    let t = T { a: 2, p: &w };
+    assert_eq!(t.a, 2);
+    assert_eq!(*t.p, 1);
+
+    let pairs: [(&u32, u32); 2] = [(&v, 1), (&v, 2)];
+    assert_eq!(pairs[0].1 + pairs[1].1, 3);
+}
@@ -0,0 +1,26 @@
+#![feature(repr_simd, platform_intrinsics)]
+#![allow(non_camel_case_types)]
+
+#[repr(simd)]
+#[derive(Copy, Clone)]
+struct u32x4(u32, u32, u32, u32);
+
+extern "platform-intrinsic" {
+    fn simd_add<T>(x: T, y: T) -> T;
+    fn simd_sub<T>(x: T, y: T) -> T;
+    fn simd_mul<T>(x: T, y: T) -> T;
+}
+
+fn main() {
+    let a = u32x4(1, 2, 3, 4);
+    let b = u32x4(10, 20, 30, 40);
+
+    let sum = unsafe { simd_add(a, b) };
+    assert_eq!((sum.0, sum.1, sum.2, sum.3), (11, 22, 33, 44));
+
+    let diff = unsafe { simd_sub(b, a) };
+    assert_eq!((diff.0, diff.1, diff.2, diff.3), (9, 18, 27, 36));
+
+    let prod = unsafe { simd_mul(a, b) };
+    assert_eq!((prod.0, prod.1, prod.2, prod.3), (10, 40, 90, 160));
+}
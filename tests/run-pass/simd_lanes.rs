@@ -0,0 +1,23 @@
+#![feature(repr_simd, platform_intrinsics)]
+#![allow(non_camel_case_types)]
+
+#[repr(simd)]
+#[derive(Copy, Clone)]
+struct u32x4(u32, u32, u32, u32);
+
+extern "platform-intrinsic" {
+    fn simd_extract<T, E>(x: T, idx: u32) -> E;
+    fn simd_insert<T, E>(x: T, idx: u32, val: E) -> T;
+}
+
+fn main() {
+    let v = u32x4(1, 2, 3, 4);
+
+    let lane: u32 = unsafe { simd_extract(v, 2) };
+    assert_eq!(lane, 3);
+
+    let w = unsafe { simd_insert(v, 0, 42u32) };
+    assert_eq!((w.0, w.1, w.2, w.3), (42, 2, 3, 4));
+    // the original vector is left untouched
+    assert_eq!(v.0, 1);
+}
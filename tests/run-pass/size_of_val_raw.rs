@@ -0,0 +1,24 @@
+#![feature(intrinsics)]
+
+extern "rust-intrinsic" {
+    fn size_of_val_raw<T: ?Sized>(ptr: *const T) -> usize;
+    fn min_align_of_val_raw<T: ?Sized>(ptr: *const T) -> usize;
+}
+
+trait Trait {}
+impl Trait for u16 {}
+
+fn main() {
+    let a = [1u32, 2, 3, 4, 5];
+    let slice: *const [u32] = &a[1..4];
+    let x = 7u16;
+    let object: *const Trait = &x;
+    unsafe {
+        assert_eq!(size_of_val_raw(slice), 12);
+        assert_eq!(min_align_of_val_raw(slice), 4);
+        assert_eq!(size_of_val_raw("hello" as *const str), 5);
+        assert_eq!(size_of_val_raw(object), 2);
+        assert_eq!(min_align_of_val_raw(object), 2);
+        assert_eq!(size_of_val_raw(&0u64 as *const u64), 8);
+    }
+}
@@ -0,0 +1,34 @@
+#![feature(advanced_slice_patterns, slice_patterns)]
+
+fn split(v: &[u32]) -> (u32, u32, &[u32]) {
+    match *v {
+        [a, b, ref rest..] => (a, b, rest),
+        _ => (0, 0, &[]),
+    }
+}
+
+fn ends(v: &[u32]) -> Option<(u32, u32)> {
+    match *v {
+        [first, .., last] => Some((first, last)),
+        _ => None,
+    }
+}
+
+fn main() {
+    let v = [1u32, 2, 3, 4, 5];
+    let (a, b, rest) = split(&v);
+    assert_eq!((a, b), (1, 2));
+    assert_eq!(rest, &[3, 4, 5]);
+    assert_eq!(split(&v[3..]), (4, 5, &[][..]));
+    assert_eq!(split(&v[..1]), (0, 0, &[][..]));
+
+    assert_eq!(ends(&v), Some((1, 5)));
+    assert_eq!(ends(&v[1..3]), Some((2, 3)));
+    assert_eq!(ends(&v[..1]), None);
+
+    // subslices of arrays are arrays
+    let [x, ref middle.., y] = v;
+    assert_eq!((x, y), (1, 5));
+    assert_eq!(middle, &[2, 3, 4]);
+    assert_eq!(middle.len(), 3);
+}
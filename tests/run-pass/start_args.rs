@@ -0,0 +1,30 @@
+#![feature(custom_attribute, start)]
+#![miri(arg = "miri")]
+#![miri(arg = "hello")]
+#![miri(env = "GREETING=hi")]
+
+extern {
+    fn getenv(name: *const u8) -> *const u8;
+}
+
+unsafe fn c_str<'a>(p: *const u8) -> &'a [u8] {
+    let mut len = 0;
+    while *p.offset(len) != 0 {
+        len += 1;
+    }
+    std::slice::from_raw_parts(p, len as usize)
+}
+
+#[start]
+fn start(argc: isize, argv: *const *const u8) -> isize {
+    assert_eq!(argc, 2);
+    unsafe {
+        assert_eq!(c_str(*argv), b"miri");
+        assert_eq!(c_str(*argv.offset(1)), b"hello");
+        assert!((*argv.offset(2)).is_null());
+
+        assert_eq!(c_str(getenv(b"GREETING\0".as_ptr())), b"hi");
+        assert!(getenv(b"MISSING\0".as_ptr()).is_null());
+    }
+    0
+}
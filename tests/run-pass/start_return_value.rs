@@ -0,0 +1,12 @@
+#![feature(start)]
+
+fn answer() -> isize {
+    (1..7).fold(0, |acc, x| acc + x) * 2
+}
+
+// Both natively and under miri the process exits with the returned status, so this only passes
+// if the computed value is exactly 42.
+#[start]
+fn start(_argc: isize, _argv: *const *const u8) -> isize {
+    answer() - 42
+}
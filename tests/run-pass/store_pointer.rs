@@ -0,0 +1,16 @@
+fn main() {
+    let arr = [10u32, 20, 30];
+    // a pointer into the middle of `arr`, stored in a separate heap allocation
+    let boxed: Box<&u32> = Box::new(&arr[1]);
+    assert_eq!(**boxed, 20);
+    assert!(*boxed as *const u32 == &arr[1] as *const u32);
+
+    let mut slots: [Option<&u32>; 2] = [None, None];
+    slots[1] = Some(&arr[2]);
+    assert!(slots[0].is_none());
+    assert_eq!(slots[1].map(|p| *p), Some(30));
+
+    let raw = &arr[0] as *const u32;
+    let stored = Box::new(raw);
+    assert_eq!(unsafe { *stored.offset(2) }, 30);
+}
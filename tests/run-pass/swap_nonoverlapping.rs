@@ -0,0 +1,22 @@
+#![feature(intrinsics)]
+
+extern "rust-intrinsic" {
+    fn swap_nonoverlapping<T>(a: *mut T, b: *mut T, count: usize);
+}
+
+fn main() {
+    let (x1, x2, x3, y1, y2, y3) = (1, 2, 3, 4, 5, 6);
+    let mut a: [&i32; 3] = [&x1, &x2, &x3];
+    let mut b: [&i32; 3] = [&y1, &y2, &y3];
+    unsafe { swap_nonoverlapping(a.as_mut_ptr(), b.as_mut_ptr(), 3); }
+    assert_eq!((*a[0], *a[1], *a[2]), (4, 5, 6));
+    assert_eq!((*b[0], *b[1], *b[2]), (1, 2, 3));
+
+    // adjacent but disjoint halves of the same array
+    let mut c = [1u8, 2, 3, 4];
+    unsafe {
+        let p = c.as_mut_ptr();
+        swap_nonoverlapping(p, p.offset(2), 2);
+    }
+    assert_eq!(c, [3, 4, 1, 2]);
+}
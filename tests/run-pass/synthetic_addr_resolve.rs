@@ -0,0 +1,24 @@
+#![feature(custom_attribute, attr_literals)]
+#![miri(deterministic_addresses=true)]
+
+struct Pair {
+    a: u64,
+    b: u16,
+}
+
+fn main() {
+    let x = [10u8, 20, 30, 40];
+    let pair = Box::new(Pair { a: 1, b: 2 });
+
+    let x_addr = &x as *const [u8; 4] as usize;
+    let b_addr = &pair.b as *const u16 as usize;
+    assert!(x_addr != b_addr);
+
+    // each address maps back into the allocation and offset it was taken from
+    for i in 0..4 {
+        assert_eq!(unsafe { *((x_addr + i) as *const u8) }, x[i]);
+    }
+    assert_eq!(unsafe { *(b_addr as *const u16) }, 2);
+    let a_addr = &pair.a as *const u64 as usize;
+    assert_eq!(unsafe { *(a_addr as *const u64) }, 1);
+}
@@ -0,0 +1,18 @@
+fn bytes_of(x: &u32) -> [u8; 4] {
+    let p = x as *const u32 as *const u8;
+    unsafe { [*p, *p.offset(1), *p.offset(2), *p.offset(3)] }
+}
+
+#[cfg(target_endian = "little")]
+const EXPECTED: [u8; 4] = [0x78, 0x56, 0x34, 0x12];
+
+#[cfg(target_endian = "big")]
+const EXPECTED: [u8; 4] = [0x12, 0x34, 0x56, 0x78];
+
+fn main() {
+    let x = 0x12345678u32;
+    assert_eq!(bytes_of(&x), EXPECTED);
+
+    let y: u32 = unsafe { ::std::mem::transmute(EXPECTED) };
+    assert_eq!(y, x);
+}
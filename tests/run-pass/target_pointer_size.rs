@@ -0,0 +1,24 @@
+use std::mem::size_of;
+
+#[cfg(target_pointer_width = "32")]
+const PTR_SIZE: usize = 4;
+
+#[cfg(target_pointer_width = "64")]
+const PTR_SIZE: usize = 8;
+
+fn main() {
+    assert_eq!(size_of::<usize>(), PTR_SIZE);
+    assert_eq!(size_of::<*const u8>(), PTR_SIZE);
+    assert_eq!(size_of::<&[u8]>(), 2 * PTR_SIZE);
+
+    // pointers stored in memory are read back with the target's width
+    let x = 5u8;
+    let slots: [&u8; 3] = [&x, &x, &x];
+    let copy = slots;
+    assert_eq!(*copy[1], 5);
+
+    let big = usize::max_value();
+    let arr = [big, 1];
+    assert_eq!(arr[0], big);
+    assert_eq!(arr[1], 1);
+}
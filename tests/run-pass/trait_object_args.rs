@@ -0,0 +1,46 @@
+trait Animal {
+    fn legs(&self) -> u32;
+    fn name(&self) -> &'static str;
+}
+
+struct Dog;
+struct Bird {
+    flying: bool,
+}
+
+impl Animal for Dog {
+    fn legs(&self) -> u32 { 4 }
+    fn name(&self) -> &'static str { "dog" }
+}
+
+impl Animal for Bird {
+    fn legs(&self) -> u32 { 2 }
+    fn name(&self) -> &'static str {
+        if self.flying { "flying bird" } else { "bird" }
+    }
+}
+
+fn legs(a: &Animal) -> u32 {
+    a.legs()
+}
+
+fn describe(a: &Animal, count: u32) -> (&'static str, u32) {
+    (a.name(), legs(a) * count)
+}
+
+fn total_legs(animals: &[&Animal]) -> u32 {
+    animals.iter().map(|a| a.legs()).fold(0, |x, y| x + y)
+}
+
+fn main() {
+    let dog = Dog;
+    let bird = Bird { flying: true };
+    assert_eq!(legs(&dog), 4);
+    assert_eq!(legs(&bird), 2);
+    assert_eq!(describe(&dog, 3), ("dog", 12));
+    assert_eq!(describe(&bird, 1), ("flying bird", 2));
+    assert_eq!(total_legs(&[&dog, &bird, &Bird { flying: false }]), 8);
+
+    let boxed: Box<Animal> = Box::new(Bird { flying: false });
+    assert_eq!(describe(&*boxed, 2), ("bird", 4));
+}
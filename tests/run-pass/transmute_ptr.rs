@@ -0,0 +1,25 @@
+#![feature(custom_attribute, attr_literals)]
+#![miri(deterministic_addresses=true)]
+
+use std::mem::transmute;
+
+fn main() {
+    let x = 42;
+    let r = &x;
+
+    // transmuting between pointer types keeps the pointer usable
+    let p = unsafe { transmute::<&i32, *const i32>(r) };
+    assert_eq!(unsafe { *p }, 42);
+    let r2 = unsafe { transmute::<*const i32, &i32>(p) };
+    assert_eq!(*r2, 42);
+
+    // transmuting to an integer yields the same address as casting
+    let addr = unsafe { transmute::<&i32, usize>(r) };
+    assert_eq!(addr, r as *const i32 as usize);
+    assert!(addr != 0);
+
+    // also when the reference is read from memory instead of a local
+    let rr = &r;
+    let addr2 = unsafe { transmute::<&i32, usize>(*rr) };
+    assert_eq!(addr2, addr);
+}
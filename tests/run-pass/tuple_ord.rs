@@ -0,0 +1,22 @@
+use std::cmp::Ordering;
+use std::f32;
+
+fn main() {
+    assert!((1, 2) < (1, 3));
+    assert!((1, 3) > (1, 2));
+    assert!((0, 9) < (1, 0));
+    assert!((2, 0) >= (1, 9));
+    assert!((1, 2) <= (1, 2));
+    assert!(!((1, 2) < (1, 2)));
+
+    assert_eq!((1u8, 2i32, 3u64).cmp(&(1, 2, 3)), Ordering::Equal);
+    assert_eq!((1u8, -2i32, 3u64).cmp(&(1, 2, 0)), Ordering::Less);
+    assert_eq!((1u8, 2i32, 4u64).cmp(&(1, 2, 3)), Ordering::Greater);
+
+    // later fields are not consulted once an earlier one differs
+    assert_eq!((1, f32::NAN).partial_cmp(&(2, 0.0)), Some(Ordering::Less));
+    assert_eq!((1, f32::NAN).partial_cmp(&(1, 0.0)), None);
+
+    assert_eq!(::std::cmp::max((3, 1), (2, 7)), (3, 1));
+    assert_eq!(::std::cmp::min((3, 1), (3, 0)), (3, 0));
+}
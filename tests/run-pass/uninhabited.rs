@@ -0,0 +1,26 @@
+enum Void {}
+
+fn parse(s: &str) -> Result<i32, Void> {
+    Ok(s.len() as i32)
+}
+
+fn unwrap<T>(r: Result<T, Void>) -> T {
+    match r {
+        Ok(x) => x,
+        Err(v) => match v {},
+    }
+}
+
+fn main() {
+    assert_eq!(unwrap(parse("abc")), 3);
+    assert_eq!(std::mem::size_of::<Void>(), 0);
+    assert_eq!(std::mem::size_of::<Result<i32, Void>>(), std::mem::size_of::<Result<i32, ()>>());
+
+    let mut total = 0;
+    for s in &["a", "bb"] {
+        if let Ok(n) = parse(s) {
+            total += n;
+        }
+    }
+    assert_eq!(total, 3);
+}
@@ -0,0 +1,21 @@
+#![feature(custom_attribute, attr_literals)]
+#![miri(check_uninit=false)]
+
+// With `check_uninit` disabled, reading undefined bytes yields zeros instead of an error.
+
+#[repr(C)]
+struct Padded {
+    a: u8,
+    b: u32,
+}
+
+fn main() {
+    let v: Vec<u8> = Vec::with_capacity(10);
+    let undef = unsafe { *v.get_unchecked(5) };
+    assert_eq!(undef + 1, 1);
+
+    let p = Padded { a: 1, b: 2 };
+    let bytes = &p as *const Padded as *const u8;
+    let pad = unsafe { *bytes.offset(1) };
+    assert_eq!(pad, 0);
+}
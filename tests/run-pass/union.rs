@@ -0,0 +1,15 @@
+#![feature(untagged_unions)]
+
+union U {
+    a: u32,
+    b: f32,
+}
+
+fn main() {
+    let mut u = U { a: 0x3f80_0000 };
+    unsafe {
+        assert_eq!(u.b, 1.0);
+        u.b = 2.0;
+        assert_eq!(u.a, 0x4000_0000);
+    }
+}
@@ -0,0 +1,36 @@
+fn push_pop() -> Vec<i32> {
+    let mut v = Vec::new();
+    for i in 0..10 {
+        v.push(i * i);
+    }
+    assert_eq!(v.len(), 10);
+    assert_eq!(v.pop(), Some(81));
+    assert_eq!(v.pop(), Some(64));
+    v.push(-1);
+    v
+}
+
+fn pop_all() -> usize {
+    let mut v = Vec::with_capacity(2);
+    v.push(1u16);
+    v.push(2);
+    v.push(3);
+    let mut sum = 0;
+    while let Some(x) = v.pop() {
+        sum += x as usize;
+    }
+    assert_eq!(v.pop(), None);
+    // shrinking an empty vector frees its buffer
+    v.shrink_to_fit();
+    sum
+}
+
+fn main() {
+    let v = push_pop();
+    assert_eq!(v.len(), 9);
+    assert_eq!(v[0], 0);
+    assert_eq!(v[3], 9);
+    assert_eq!(v[7], 49);
+    assert_eq!(v[8], -1);
+    assert_eq!(pop_all(), 6);
+}
@@ -0,0 +1,34 @@
+#![feature(core_intrinsics)]
+
+use std::intrinsics::{volatile_copy_memory, volatile_set_memory};
+use std::ptr;
+
+fn main() {
+    let mut a = [1u16; 5];
+    unsafe {
+        volatile_set_memory(a.as_mut_ptr().offset(1), 0xab, 3);
+    }
+    assert_eq!(a, [1, 0xabab, 0xabab, 0xabab, 1]);
+
+    let mut b = [0u32; 4];
+    unsafe {
+        ptr::write_bytes(b.as_mut_ptr(), 0xff, 2);
+    }
+    assert_eq!(b, [!0, !0, 0, 0]);
+
+    // overlapping copy towards the end of the array
+    let mut c = [1u8, 2, 3, 4, 5];
+    unsafe {
+        let p = c.as_mut_ptr();
+        volatile_copy_memory(p.offset(1), p, 3);
+    }
+    assert_eq!(c, [1, 1, 2, 3, 5]);
+
+    // overlapping copy towards the start of the array
+    let mut d = [1u8, 2, 3, 4, 5];
+    unsafe {
+        let p = d.as_mut_ptr();
+        volatile_copy_memory(p, p.offset(2), 3);
+    }
+    assert_eq!(d, [3, 4, 5, 4, 5]);
+}
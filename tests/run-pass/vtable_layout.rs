@@ -0,0 +1,29 @@
+use std::mem;
+
+trait Answer {
+    fn answer(&self) -> u32;
+}
+
+struct Wide(u16, u64);
+
+impl Answer for Wide {
+    fn answer(&self) -> u32 {
+        self.0 as u32 + self.1 as u32
+    }
+}
+
+fn main() {
+    let w = Wide(40, 2);
+    let object: &Answer = &w;
+    assert_eq!(object.answer(), 42);
+
+    // A vtable starts with the drop glue, the size and the alignment, followed by the methods.
+    let (data, vtable): (*const u8, *const usize) = unsafe { mem::transmute(object) };
+    assert_eq!(data, &w as *const Wide as *const u8);
+    unsafe {
+        assert_eq!(*vtable.offset(1), mem::size_of::<Wide>());
+        assert_eq!(*vtable.offset(2), mem::align_of::<Wide>());
+    }
+    assert_eq!(mem::size_of_val(object), mem::size_of::<Wide>());
+    assert_eq!(mem::align_of_val(object), mem::align_of::<Wide>());
+}
@@ -0,0 +1,10 @@
+fn main() {
+    assert_eq!(std::i32::MIN.wrapping_div(-1), std::i32::MIN);
+    assert_eq!(std::i32::MIN.wrapping_rem(-1), 0);
+    assert_eq!(std::i32::MIN.overflowing_div(-1), (std::i32::MIN, true));
+    assert_eq!(std::i32::MIN.checked_div(-1), None);
+    assert_eq!(5i32.checked_div(0), None);
+    assert_eq!(7u8.wrapping_div(2), 3);
+    assert_eq!(255u8.wrapping_add(1), 0);
+    assert_eq!(0u32.wrapping_sub(1), std::u32::MAX);
+}
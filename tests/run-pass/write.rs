@@ -0,0 +1,9 @@
+extern {
+    fn write(fd: i32, buf: *const u8, count: usize) -> isize;
+}
+
+fn main() {
+    let msg = b"hello\n";
+    let written = unsafe { write(1, msg.as_ptr(), msg.len()) };
+    assert_eq!(written, 6);
+}
@@ -0,0 +1,18 @@
+use std::ptr;
+
+fn main() {
+    let mut buf = [7u8; 4096];
+    assert!(buf.iter().all(|&b| b == 7));
+    unsafe { ptr::write_bytes(buf.as_mut_ptr().offset(100), 0, 16); }
+    assert_eq!(buf[99], 7);
+    assert!(buf[100..116].iter().all(|&b| b == 0));
+    assert_eq!(buf[116], 7);
+
+    // Overwriting a pointer with plain bytes removes it, so the bytes read back as an integer.
+    let x = 42;
+    let mut refs = [&x; 2];
+    unsafe { ptr::write_bytes(&mut refs[0] as *mut &i32 as *mut u8, 0xff, std::mem::size_of::<&i32>()); }
+    let bits = unsafe { *(&refs[0] as *const &i32 as *const usize) };
+    assert_eq!(bits, !0);
+    assert_eq!(*refs[1], 42);
+}
@@ -0,0 +1,24 @@
+use std::ptr;
+use std::slice;
+
+fn main() {
+    let a = [1u8, 2, 3, 4];
+    let mut b = [0u8; 4];
+    unsafe {
+        // Copying zero bytes between two allocations touches neither of them.
+        ptr::copy(a.as_ptr(), b.as_mut_ptr(), 0);
+        ptr::copy_nonoverlapping(a.as_ptr().offset(4), b.as_mut_ptr().offset(4), 0);
+    }
+    assert_eq!(b, [0; 4]);
+
+    // Reading zero bytes at the one-past-the-end offset is fine.
+    let end = unsafe { slice::from_raw_parts(a.as_ptr().offset(4), 0) };
+    assert_eq!(end, &[] as &[u8]);
+    assert_eq!(&a[4..], &[] as &[u8]);
+    let unit = unsafe { ptr::read(a.as_ptr().offset(4) as *const ()) };
+    assert_eq!(unit, ());
+
+    let empty: [u32; 0] = [];
+    let copy = empty;
+    assert_eq!(copy.len(), 0);
+}
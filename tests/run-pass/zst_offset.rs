@@ -0,0 +1,8 @@
+fn main() {
+    let x = ();
+    let p = &x as *const ();
+    let q = unsafe { p.offset(1 << 40) };
+    assert_eq!(p, q);
+    let r = unsafe { q.offset(-(1 << 40)) };
+    assert_eq!(p, r);
+}
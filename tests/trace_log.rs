@@ -0,0 +1,59 @@
+//! Tests of the trace records the interpreter logs while stepping.
+
+#![feature(rustc_private)]
+
+extern crate log;
+extern crate miri;
+extern crate rustc;
+
+mod helpers;
+
+use log::{LogLevelFilter, LogMetadata, LogRecord};
+use miri::EvalContext;
+use rustc::hir::def_id::DefId;
+use rustc::mir::mir_map::MirMap;
+use rustc::ty::TyCtxt;
+use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+
+/// The number of statement and terminator records logged by the interpreter so far.
+static LOCATION_RECORDS: AtomicUsize = ATOMIC_USIZE_INIT;
+
+struct CaptureLogger;
+
+impl log::Log for CaptureLogger {
+    fn enabled(&self, metadata: &LogMetadata) -> bool {
+        metadata.target().starts_with("miri::interpreter")
+    }
+
+    fn log(&self, record: &LogRecord) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let text = record.args().to_string();
+        if text.starts_with("bb") && text.contains("]: ") {
+            LOCATION_RECORDS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+#[test]
+fn one_record_per_statement_and_terminator() {
+    fn check<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir_map: &'a MirMap<'tcx>, main: DefId) {
+        // `main` has no branches, so every block runs exactly once.
+        let mir = mir_map.map.get(&main).unwrap();
+        let statements: usize = mir.basic_blocks().iter().map(|block| block.statements.len()).sum();
+        let expected = statements + mir.basic_blocks().len();
+
+        let mut ecx = EvalContext::new(tcx, mir_map, 1024 * 1024, 100);
+        ecx.push_main_frame(main, &[]).unwrap();
+        let before = LOCATION_RECORDS.load(Ordering::SeqCst);
+        while ecx.step().unwrap() {}
+        assert_eq!(LOCATION_RECORDS.load(Ordering::SeqCst) - before, expected);
+    }
+
+    log::set_logger(|max_level| {
+        max_level.set(LogLevelFilter::Trace);
+        Box::new(CaptureLogger)
+    }).unwrap();
+    helpers::run("straight_line", check);
+}